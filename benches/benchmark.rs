@@ -1,7 +1,18 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::thread;
 use visdom::types::BoxDynError;
 use visdom::Vis;
 
+const SELECTORS: [&str; 6] = [
+	"ul > li:nth-child(2n+1)",
+	"li.item a[href^='/product']",
+	"div#content .list li:not(.active)",
+	"ul li:has(> a), ul li:contains('item')",
+	"[data-id] > span + a",
+	"li:first-child ~ li:last-child",
+];
+const QUERY_TIMES: usize = 100;
+
 fn bench_selector() -> Result<(), BoxDynError> {
 	let html: String = format!(
 		r##"
@@ -16,8 +27,48 @@ fn bench_selector() -> Result<(), BoxDynError> {
 	list.find("li:nth-child(2n)");
 	Ok(())
 }
+
+// parse the selectors in a small document, the selector parsing is the main cost
+fn query_selectors() {
+	let html = r##"
+    <div id="content">
+      <ul class="list">
+        <li class="item" data-id="1"><span>1</span><a href="/product/1">item1</a></li>
+        <li class="item active" data-id="2"><span>2</span><a href="/product/2">item2</a></li>
+      </ul>
+    </div>
+  "##;
+	let root = Vis::load(html).unwrap();
+	for _ in 0..QUERY_TIMES {
+		for selector in SELECTORS {
+			root.find(selector);
+		}
+	}
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
 	c.bench_function("bench-selector", |b| b.iter(bench_selector));
+	// parallel selector parsing, the throughput should increase with the threads
+	let mut group = c.benchmark_group("bench-parallel-selector");
+	for threads in [1, 2, 4, 8] {
+		group.throughput(Throughput::Elements(
+			(threads * QUERY_TIMES * SELECTORS.len()) as u64,
+		));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(threads),
+			&threads,
+			|b, &threads| {
+				b.iter(|| {
+					thread::scope(|s| {
+						for _ in 0..threads {
+							s.spawn(query_selectors);
+						}
+					})
+				})
+			},
+		);
+	}
+	group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
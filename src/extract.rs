@@ -0,0 +1,317 @@
+//! Declarative data extraction, turn the matched elements into records.
+//!
+//! ```
+//! use visdom::Vis;
+//! use visdom::extract::{Field, Schema, Value};
+//! use visdom::types::BoxDynError;
+//! fn main()-> Result<(), BoxDynError>{
+//!   let html = r##"
+//!     <div class="card">
+//!       <h3 class="title"> Apple </h3>
+//!       <a href="/apple">detail</a>
+//!       <span class="price">$1,200.50</span>
+//!     </div>
+//!     <div class="card">
+//!       <h3 class="title">Banana</h3>
+//!       <span class="price">sold out</span>
+//!     </div>
+//!   "##;
+//!   let schema = Schema::new(".card")?
+//!     .field("title", Field::text(".title")?.trim())
+//!     .field("link", Field::attr("a", "href")?)
+//!     .field("price", Field::text(".price")?.capture(r"[\d,.]+")?.number());
+//!   let records = schema.extract(&Vis::load(html)?);
+//!   assert_eq!(records.len(), 2);
+//!   assert_eq!(records[0]["title"], Value::Text(String::from("Apple")));
+//!   assert_eq!(records[0]["link"], Value::Text(String::from("/apple")));
+//!   assert_eq!(records[0]["price"], Value::Number(1200.5));
+//!   assert_eq!(records[1]["link"], Value::Null);
+//!   assert_eq!(records[1]["price"], Value::Null);
+//!   Ok(())
+//! }
+//! ```
+use crate::mesdoc::error::{BoxDynError, Error as IError};
+use crate::mesdoc::interface::Elements;
+use crate::mesdoc::selector::CompiledSelector;
+use crate::Vis;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// The source of a field's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+	/// `text`, the text of the element
+	Text,
+	/// `html`, the inner html of the element
+	Html,
+	/// `outer_html`, the outer html of the element
+	OuterHtml,
+	/// `attr:NAME`, the value of the attribute
+	Attr(String),
+	/// `val`, the form value of the element
+	Val,
+}
+
+impl FromStr for Source {
+	type Err = IError;
+	fn from_str(source: &str) -> Result<Self, Self::Err> {
+		match source {
+			"text" => Ok(Source::Text),
+			"html" => Ok(Source::Html),
+			"outer_html" => Ok(Source::OuterHtml),
+			"val" => Ok(Source::Val),
+			_ => match source.strip_prefix("attr:") {
+				Some(name) if !name.trim().is_empty() => Ok(Source::Attr(String::from(name.trim()))),
+				_ => Err(IError::InvalidExtractSource {
+					context: String::from(source),
+				}),
+			},
+		}
+	}
+}
+
+/// The extracted value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	/// the element or the attribute is not found, or the post-processing failed
+	Null,
+	Text(String),
+	Number(f64),
+}
+
+impl Value {
+	/// Get the text of the value, the number will be formatted.
+	pub fn as_text(&self) -> Option<String> {
+		match self {
+			Value::Null => None,
+			Value::Text(text) => Some(text.clone()),
+			Value::Number(num) => Some(num.to_string()),
+		}
+	}
+	/// Get the number of the value.
+	pub fn as_number(&self) -> Option<f64> {
+		match self {
+			Value::Number(num) => Some(*num),
+			_ => None,
+		}
+	}
+	/// Check if the value is null.
+	pub fn is_null(&self) -> bool {
+		matches!(self, Value::Null)
+	}
+}
+
+cfg_feat_serde! {
+	impl From<Value> for serde_json::Value {
+		fn from(value: Value) -> Self {
+			match value {
+				Value::Null => serde_json::Value::Null,
+				Value::Text(text) => serde_json::Value::String(text),
+				Value::Number(num) => serde_json::Number::from_f64(num)
+					.map(serde_json::Value::Number)
+					.unwrap_or(serde_json::Value::Null),
+			}
+		}
+	}
+}
+
+/// A record of the field names and the extracted values.
+pub type Record = BTreeMap<String, Value>;
+
+#[derive(Debug)]
+enum Process {
+	Trim,
+	Capture(Regex),
+	Number,
+}
+
+/// A field of the schema, extract the value from the first element matched by the selector.
+#[derive(Debug)]
+pub struct Field {
+	selector: Option<CompiledSelector>,
+	source: Source,
+	process: Vec<Process>,
+}
+
+impl Field {
+	/// Create a field with a selector and a source string, e.g. `text`, `html`, `outer_html`, `attr:href`, `val`.
+	///
+	/// An empty selector means the scope element itself.
+	pub fn new(selector: &str, source: &str) -> Result<Self, BoxDynError> {
+		Field::with_source(selector, source.parse()?)
+	}
+	/// Create a field with a selector and a source, the attribute name of `Source::Attr` can't be empty.
+	pub fn with_source(selector: &str, source: Source) -> Result<Self, BoxDynError> {
+		if let Source::Attr(name) = &source {
+			if name.trim().is_empty() {
+				return Err(Box::new(IError::InvalidExtractSource {
+					context: format!("attr:{}", name),
+				}));
+			}
+		}
+		let selector = if selector.trim().is_empty() {
+			None
+		} else {
+			Some(Vis::compile(selector)?)
+		};
+		Ok(Field {
+			selector,
+			source,
+			process: Vec::new(),
+		})
+	}
+	/// Extract the text.
+	pub fn text(selector: &str) -> Result<Self, BoxDynError> {
+		Field::with_source(selector, Source::Text)
+	}
+	/// Extract the inner html.
+	pub fn html(selector: &str) -> Result<Self, BoxDynError> {
+		Field::with_source(selector, Source::Html)
+	}
+	/// Extract the value of the attribute, the same as the source `attr:NAME`.
+	pub fn attr(selector: &str, attr_name: &str) -> Result<Self, BoxDynError> {
+		Field::new(selector, &format!("attr:{}", attr_name))
+	}
+	/// Extract the form value.
+	pub fn val(selector: &str) -> Result<Self, BoxDynError> {
+		Field::with_source(selector, Source::Val)
+	}
+	/// Trim the whitespaces of the text.
+	pub fn trim(mut self) -> Self {
+		self.process.push(Process::Trim);
+		self
+	}
+	/// Capture the text with a regex, use the first group if the regex has groups, otherwise the whole matched text.
+	///
+	/// The value will be `Value::Null` if the regex is not matched.
+	pub fn capture(mut self, regex: &str) -> Result<Self, BoxDynError> {
+		self.process.push(Process::Capture(Regex::new(regex)?));
+		Ok(self)
+	}
+	/// Parse the text into a number, the thousands separators `,` are ignored.
+	///
+	/// The value will be `Value::Null` if the text is not a finite number, e.g. `NaN`, `inf`.
+	pub fn number(mut self) -> Self {
+		self.process.push(Process::Number);
+		self
+	}
+	// extract the value from the scope element
+	fn extract(&self, scope: &Elements) -> Value {
+		let target = match &self.selector {
+			Some(selector) => scope.find_compiled(selector).first(),
+			None => scope.first(),
+		};
+		if target.is_empty() {
+			return Value::Null;
+		}
+		let value = match &self.source {
+			Source::Text => Some(target.text()),
+			Source::Html => Some(target.html()),
+			Source::OuterHtml => Some(target.outer_html()),
+			Source::Attr(name) => target.attr(name).map(|value| value.to_string()),
+			Source::Val => Some(target.val().to_string()),
+		};
+		let mut value = match value {
+			Some(value) => Value::Text(value),
+			None => Value::Null,
+		};
+		for process in &self.process {
+			let text = match value.as_text() {
+				Some(text) => text,
+				None => return Value::Null,
+			};
+			value = match process {
+				Process::Trim => Value::Text(String::from(text.trim())),
+				Process::Capture(regex) => regex
+					.captures(&text)
+					.and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+					.map(|matched| Value::Text(String::from(matched.as_str())))
+					.unwrap_or(Value::Null),
+				Process::Number => text
+					.trim()
+					.replace(',', "")
+					.parse::<f64>()
+					.ok()
+					.filter(|num| num.is_finite())
+					.map(Value::Number)
+					.unwrap_or(Value::Null),
+			};
+		}
+		value
+	}
+}
+
+/// A schema of named fields, extract a record for each element matched by the scope selector.
+#[derive(Debug)]
+pub struct Schema {
+	scope: Option<CompiledSelector>,
+	fields: Vec<(String, Field)>,
+}
+
+impl Schema {
+	/// Create a schema, an empty scope selector means extract only one record from the elements.
+	pub fn new(scope: &str) -> Result<Self, BoxDynError> {
+		let scope = if scope.trim().is_empty() {
+			None
+		} else {
+			Some(Vis::compile(scope)?)
+		};
+		Ok(Schema {
+			scope,
+			fields: Vec::new(),
+		})
+	}
+	/// Add a field, the field with a same name will be replaced.
+	pub fn field(mut self, name: &str, field: Field) -> Self {
+		if let Some(index) = self.fields.iter().position(|(cur, _)| cur == name) {
+			self.fields[index].1 = field;
+		} else {
+			self.fields.push((String::from(name), field));
+		}
+		self
+	}
+	// extract a record from the scope element
+	fn extract_record(&self, scope: &Elements) -> Record {
+		self
+			.fields
+			.iter()
+			.map(|(name, field)| (name.clone(), field.extract(scope)))
+			.collect()
+	}
+	/// Extract the records from the elements.
+	pub fn extract(&self, root: &Elements) -> Vec<Record> {
+		match &self.scope {
+			Some(scope) => root
+				.find_compiled(scope)
+				.get_ref()
+				.iter()
+				.map(|ele| self.extract_record(&Vis::dom(ele)))
+				.collect(),
+			None => {
+				if root.is_empty() {
+					vec![]
+				} else {
+					vec![self.extract_record(root)]
+				}
+			}
+		}
+	}
+	cfg_feat_serde! {
+		/// Extract the records as json values.
+		pub fn extract_json(&self, root: &Elements) -> Vec<serde_json::Value> {
+			self
+				.extract(root)
+				.into_iter()
+				.map(|record| {
+					serde_json::Value::Object(
+						record
+							.into_iter()
+							.map(|(name, value)| (name, value.into()))
+							.collect(),
+					)
+				})
+				.collect()
+		}
+	}
+}
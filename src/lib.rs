@@ -11,6 +11,7 @@
 //! - Well tested: the unit tests have covered most cases, but if you meet any bugs or questions, welcome to submit issues or PR to us.
 #[macro_use]
 mod macros;
+pub mod extract;
 mod mesdoc;
 // feature="text"
 cfg_feat_text! {
@@ -25,18 +26,22 @@ use mesdoc::interface::{
 	IErrorHandle, IFormValue, INodeTrait, ITextTrait, IUncareNodeTrait, MaybeDoc, MaybeElement,
 };
 
+use mesdoc::rules::custom::{add_pseudo, PseudoHandle};
+use mesdoc::rules::namespace::with_namespaces;
 use mesdoc::utils::is_equal_chars;
 use mesdoc::{error::Error as IError, utils::retain_by_index};
 use rphtml::{
 	config::RenderOptions,
 	entity::{encode, encode_char, CharacterSet, EncodeType, ICodedDataTrait},
 	parser::{
-		allow_insert, is_content_tag, Attr, AttrData, Doc, DocHolder, NameCase, Node, NodeType, RefNode,
+		allow_insert, is_content_tag, Attr, AttrData, Doc, DocHolder, NameCase, Node, NodeType,
+		RefNode, TagMeta,
 	},
 };
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::{any::Any, cell::RefCell};
 // re export `IAttrValue` `IEnumTyped` `INodeType`
 pub mod types {
@@ -44,12 +49,17 @@ pub mod types {
 	cfg_feat_text! {
 		pub use crate::mesdoc::interface::Texts;
 	}
+	// insertion
+	cfg_feat_insertion! {
+		pub use crate::mesdoc::interface::InsertPosition;
+	}
 	pub use crate::mesdoc::error::BoxDynError;
 	pub use crate::mesdoc::interface::{
-		BoxDynElement, BoxDynNode, BoxDynText, Elements, IAttrValue, IDocumentTrait, IEnumTyped,
-		IFormValue, INodeType,
+		BoxDynElement, BoxDynNode, BoxDynText, BoxDynUncareNode, DataValue, Elements, IAttrValue,
+		IDocumentTrait, IElementTrait, IEnumTyped, IFormValue, INodeType, IUncareNodeTrait, Nodes,
 	};
-	pub use crate::mesdoc::selector::Combinator;
+	pub use crate::mesdoc::rules::custom::PseudoParam;
+	pub use crate::mesdoc::selector::{Combinator, CompiledSelector, Explanation, Specificity};
 }
 
 // re export `ParseOptions` and `error`
@@ -59,7 +69,7 @@ pub mod html {
 }
 
 use crate::html::ParseOptions;
-use crate::types::{BoxDynError, IAttrValue, IEnumTyped, INodeType};
+use crate::types::{BoxDynError, CompiledSelector, IAttrValue, IEnumTyped, INodeType, PseudoParam};
 /// type implement INodeTrait with Node
 struct Dom;
 
@@ -72,6 +82,107 @@ impl Dom {
 			}));
 		}
 	}
+	// remove the node from its parent's child nodes
+	fn remove_node(dom: &Rc<RefCell<Node>>) {
+		let index = dom.index();
+		if let Some(parent) = &dom.borrow_mut().parent {
+			if let Some(parent) = parent.upgrade() {
+				if let Some(childs) = &mut parent.borrow_mut().childs {
+					childs.remove(index);
+					// change next siblings index
+					reset_next_siblings_index(index, &childs[index..]);
+				}
+			}
+		}
+	}
+	// check if the data can be the content of the comment or CDATA node
+	fn check_node_data(node_type: NodeType, data: &str) -> Result<(), &'static str> {
+		match node_type {
+			NodeType::Comment => {
+				if data.starts_with('>') || data.starts_with("->") {
+					return Err("the comment can't start with '>' or '->'");
+				}
+				if data.contains("-->") || data.contains("--!>") || data.ends_with("<!-") {
+					return Err("the comment can't contain '-->' or '--!>', or end with '<!-'");
+				}
+				Ok(())
+			}
+			NodeType::XMLCDATA => {
+				if data.contains("]]>") {
+					return Err("the CDATA can't contain ']]>'");
+				}
+				Ok(())
+			}
+			_ => Err("only the comment and CDATA nodes have data"),
+		}
+	}
+	// create a comment or CDATA node
+	fn create_data_node(node_type: NodeType, data: &str) -> Result<Node, IError> {
+		Dom::check_node_data(node_type, data).map_err(|reason| IError::InvalidNodeData {
+			data: String::from(data),
+			reason: String::from(reason),
+		})?;
+		let mut node = Node::new(node_type, 0);
+		node.content = Some(data.chars().collect());
+		Ok(node)
+	}
+	// check if the name can be a tag name, e.g. `div`, `my-element`, `svg:rect`
+	fn is_valid_tag_name(name: &str) -> bool {
+		let mut chars = name.chars();
+		match chars.next() {
+			Some(ch) if ch.is_ascii_alphabetic() => chars.all(|ch| {
+				!ch.is_whitespace() && !ch.is_control() && !matches!(ch, '/' | '>' | '<' | '=' | '"' | '\'')
+			}),
+			_ => false,
+		}
+	}
+	// the keywords and the quoted identifiers of the doctype, e.g. `html`, `PUBLIC`, `"-//W3C//DTD HTML 4.01//EN"`
+	fn doctype_parts(dom: &Rc<RefCell<Node>>) -> Vec<(bool, String)> {
+		let node = dom.borrow();
+		if node.node_type != NodeType::HTMLDOCTYPE {
+			return vec![];
+		}
+		match &node.meta {
+			Some(meta) => meta
+				.borrow()
+				.attrs
+				.iter()
+				.flat_map(|attr| {
+					let key = attr
+						.key
+						.as_ref()
+						.map(|key| (false, key.content.iter().collect::<String>()));
+					let value = attr
+						.value
+						.as_ref()
+						.map(|value| (true, value.content.iter().collect::<String>()));
+					key.into_iter().chain(value)
+				})
+				.collect(),
+			None => vec![],
+		}
+	}
+	// the quoted identifier after the keyword `PUBLIC` or `SYSTEM`, the system identifier can follow the public identifier
+	fn doctype_id(dom: &Rc<RefCell<Node>>, is_public: bool) -> Option<String> {
+		let parts = Dom::doctype_parts(dom);
+		let keyword = parts.iter().position(|(is_quoted, part)| {
+			!is_quoted && (part.eq_ignore_ascii_case("public") || part.eq_ignore_ascii_case("system"))
+		});
+		if let Some(index) = keyword {
+			let has_public = parts[index].1.eq_ignore_ascii_case("public");
+			let mut ids = parts[index + 1..]
+				.iter()
+				.take_while(|(is_quoted, _)| *is_quoted)
+				.map(|(_, id)| id.clone());
+			let id = match (has_public, is_public) {
+				(true, false) => ids.nth(1),
+				(false, true) => None,
+				_ => ids.next(),
+			};
+			return id;
+		}
+		None
+	}
 	// when mutation feature is open
 	cfg_feat_insertion! {
 		fn validate_dom_change(dom: &Rc<RefCell<Node>>, node: &BoxDynElement, method: &str) -> bool {
@@ -114,6 +225,93 @@ impl Dom {
 			}
 			true
 		}
+		// insert the nodes to the position of the dom
+		fn insert_nodes(dom: &RefNode, position: &InsertPosition, mut nodes: Vec<RefNode>) {
+			let tag_name = dom.tag_names();
+			// insert
+			use InsertPosition::*;
+			// remove not allowed nodes, only insert childs
+			if matches!(position, AfterBegin | BeforeEnd){
+				remove_not_allowed_nodes(&tag_name, &mut nodes);
+			}
+			// check if is empty
+			if nodes.is_empty() {
+				return;
+			}
+			match position {
+				BeforeBegin | AfterEnd => {
+					// get index first, for borrow check
+					let mut index = dom.index();
+					let mut nexts: Vec<RefNode> = vec![];
+					let insert_len = nodes.len();
+					// it's insertAfter, increase the insertion index
+					if *position == AfterEnd {
+						index += 1;
+					}
+					// always reset node indexs
+					reset_next_siblings_index(index, &nodes);
+					// split to prev and next
+					if let Some(parent) = &dom.borrow_mut().parent {
+						if let Some(parent) = &parent.upgrade() {
+							if let Some(childs) = &mut parent.borrow_mut().childs {
+								// split the nexts for reset index.
+								if index < childs.len() {
+									nexts = childs.split_off(index);
+								}
+								// set node parent
+								for node in &nodes {
+									node.borrow_mut().parent = Some(Rc::downgrade(parent));
+								}
+								// insert nodes at the end
+								childs.extend(nodes);
+							}
+						}
+					}
+					if !nexts.is_empty() {
+						// reset nexts index
+						reset_next_siblings_index(index + insert_len, &nexts);
+						// for borrrow check
+						if let Some(parent) = &dom.borrow_mut().parent {
+							if let Some(parent) = parent.upgrade() {
+								if let Some(childs) = &mut parent.borrow_mut().childs {
+									//  insert nodes
+									childs.extend(nexts);
+								}
+							}
+						}
+					}
+				}
+				AfterBegin | BeforeEnd => {
+					// set nodes parent
+					for node in &nodes {
+						node.borrow_mut().parent = Some(Rc::downgrade(dom));
+					}
+					// prepend, append
+					if let Some(childs) = &mut dom.borrow_mut().childs {
+						if *position == BeforeEnd {
+							// reset nodes index
+							reset_next_siblings_index(childs.len(), &nodes);
+							// append nodes
+							childs.extend(nodes);
+						} else {
+							// always reset nodes index
+							reset_next_siblings_index(0, &nodes);
+							// reset childs index
+							reset_next_siblings_index(nodes.len(), childs);
+							// append childs to nodes
+							nodes.append(childs);
+							// set childs to nodes
+							*childs = nodes;
+						}
+						return;
+					}
+					// reset nodes index
+					reset_next_siblings_index(0, &nodes);
+					// set nodes as childs
+					dom.borrow_mut().childs = Some(nodes);
+				}
+			}
+		}
 	}
 }
 
@@ -153,6 +351,11 @@ impl INodeTrait for Rc<RefCell<Node>> {
 		self.borrow().index
 	}
 
+	/// impl `node_id`
+	fn node_id(&self) -> Option<usize> {
+		Some(Rc::as_ptr(self) as usize)
+	}
+
 	/// The current version of this method only implements the clone of `Rc` pointers.
 	/// This is different from the standard `clone_node` method.
 	/// If you want to use `clone_node` method with the standard semantics, now you can use `copy_node` instead.
@@ -397,17 +600,7 @@ impl ITextTrait for Rc<RefCell<Node>> {
 	cfg_feat_text! {
 		/// Remove a text node.
 		fn remove(self: Box<Self>) {
-			let index = self.index();
-			if let Some(parent) = &self.borrow_mut().parent {
-				if let Some(parent) = parent.upgrade() {
-					if let Some(childs) = &mut parent.borrow_mut().childs {
-						// remove the text node
-						childs.remove(index);
-						// change next siblings index
-						reset_next_siblings_index(index, &childs[index..]);
-					}
-				}
-			}
+			Dom::remove_node(&self);
 		}
 
 		// append text
@@ -432,9 +625,67 @@ impl ITextTrait for Rc<RefCell<Node>> {
 	}
 }
 
-impl IUncareNodeTrait for Rc<RefCell<Node>> {}
+impl IUncareNodeTrait for Rc<RefCell<Node>> {
+	/// impl `data`
+	fn data(&self) -> Option<String> {
+		let node = self.borrow();
+		let data = match node.node_type {
+			NodeType::HTMLDOCTYPE => node
+				.meta
+				.as_ref()
+				.map(|meta| {
+					let content: String = meta.borrow().attrs_to_string(false).into_iter().collect();
+					String::from(content.trim_start())
+				})
+				.unwrap_or_default(),
+			_ => node
+				.content
+				.as_ref()
+				.map(|content| content.iter().collect())
+				.unwrap_or_default(),
+		};
+		Some(data)
+	}
+	cfg_feat_mutation! {
+		/// impl `set_data`
+		fn set_data(&mut self, data: &str) {
+			let node_type = self.borrow().node_type;
+			match Dom::check_node_data(node_type, data) {
+				Ok(_) => self.borrow_mut().content = Some(data.chars().collect()),
+				Err(reason) => Dom::halt(self, "set_data", reason),
+			}
+		}
+		/// impl `remove`
+		fn remove(self: Box<Self>) {
+			Dom::remove_node(&self);
+		}
+	}
+	/// impl `name`
+	fn name(&self) -> Option<String> {
+		match Dom::doctype_parts(self).into_iter().next() {
+			Some((false, name)) => Some(name),
+			_ => None,
+		}
+	}
+	/// impl `public_id`
+	fn public_id(&self) -> Option<String> {
+		Dom::doctype_id(self, true)
+	}
+	/// impl `system_id`
+	fn system_id(&self) -> Option<String> {
+		Dom::doctype_id(self, false)
+	}
+}
 
 impl IElementTrait for Rc<RefCell<Node>> {
+	/// impl `cloned`, keep the node type, so the created text and comment nodes can be inserted
+	fn cloned<'b>(&self) -> BoxDynElement<'b> {
+		Box::new(self.clone())
+	}
+	/// impl `copied`
+	fn copied<'b>(&self) -> BoxDynElement<'b> {
+		Box::new(self.borrow().clone_node())
+	}
 	/// impl `names`
 	fn tag_names(&self) -> Vec<char> {
 		match self.node_type() {
@@ -611,10 +862,8 @@ impl IElementTrait for Rc<RefCell<Node>> {
 	fn get_attribute(&self, name: &str) -> Option<IAttrValue> {
 		// use lowercase to get attribute: issue: #2
 		let node = &self.borrow();
-		let meta = node
-			.meta
-			.as_ref()
-			.expect("Element node must have a meta field.");
+		// the text and comment nodes have no meta
+		let meta = node.meta.as_ref()?;
 		// if has meta, then compare with lowercase
 		let lc_name_map = &meta.borrow().lc_name_map;
 		if !lc_name_map.is_empty() {
@@ -634,10 +883,10 @@ impl IElementTrait for Rc<RefCell<Node>> {
 
 	fn get_attributes(&self) -> Vec<(String, IAttrValue)> {
 		let node = &self.borrow();
-		let meta = node
-			.meta
-			.as_ref()
-			.expect("Element node must have a meta field.");
+		let meta = match node.meta.as_ref() {
+			Some(meta) => meta,
+			None => return vec![],
+		};
 		let attrs = &meta.borrow().attrs;
 		let attr_map = meta
 			.borrow()
@@ -751,6 +1000,13 @@ impl IElementTrait for Rc<RefCell<Node>> {
 
 	/// impl `inner_html`
 	fn inner_html(&self) -> String {
+		// the text and comment nodes have no inner html
+		if !matches!(
+			self.borrow().node_type,
+			NodeType::Tag | NodeType::AbstractRoot
+		) {
+			return String::new();
+		}
 		self
 			.borrow()
 			.build(
@@ -813,7 +1069,7 @@ impl IElementTrait for Rc<RefCell<Node>> {
 			let specified: Box<dyn Any> = node.cloned().to_node();
 			if let Ok(dom) = specified.downcast::<RefNode>() {
 				// get the nodes
-				let mut nodes = match node_type {
+				let nodes = match node_type {
 					INodeType::DocumentFragement => {
 						if let Some(childs) = &dom.borrow().childs {
 							childs.iter().map(Rc::clone).collect::<Vec<RefNode>>()
@@ -829,91 +1085,68 @@ impl IElementTrait for Rc<RefCell<Node>> {
 						vec![*dom]
 					}
 				};
-				// filter the node allowed
-				let tag_name = self.tag_names();
-				// insert
-				use InsertPosition::*;
-				// remove not allowed nodes, only insert childs
-				if matches!(position, AfterBegin | BeforeEnd){
-					remove_not_allowed_nodes(&tag_name, &mut nodes);
-				}
-				// check if is empty
-				if nodes.is_empty() {
+				Dom::insert_nodes(self, position, nodes);
+			} else {
+				// not the Dom
+				Dom::halt(
+					self,
+					action,
+					&format!("Can't {} that not implemented 'Dom'", action),
+				);
+			}
+		}
+		// check if the node can be inserted into the element
+		fn allow_child(&self, node: &BoxDynElement) -> bool {
+			if self.borrow().node_type != NodeType::Tag {
+				return false;
+			}
+			let specified: Box<dyn Any> = node.cloned().to_node();
+			match specified.downcast::<RefNode>() {
+				Ok(dom) => allow_insert(&self.tag_names(), dom.borrow().node_type),
+				Err(_) => false,
+			}
+		}
+		// move the child nodes
+		fn insert_adjacent_child_nodes(&mut self, position: &InsertPosition, node: &BoxDynElement) {
+			let action = position.action();
+			let is_inside = matches!(position, InsertPosition::AfterBegin | InsertPosition::BeforeEnd);
+			if self.borrow().node_type != NodeType::Tag {
+				Dom::halt(
+					self,
+					action,
+					&format!("Can't {} for a {:?} type", action, self.borrow().node_type),
+				);
+				return;
+			}
+			// the current node can't be in the moved child nodes
+			let mut cur = if is_inside { Some(self.cloned()) } else { self.parent() };
+			while let Some(ele) = cur {
+				if ele.is(node) {
+					Dom::halt(
+						self,
+						action,
+						&format!("Can't {} the child nodes of a dom that contains it", action),
+					);
 					return;
 				}
-				match position {
-					BeforeBegin | AfterEnd => {
-						// get index first, for borrow check
-						let mut index = self.index();
-						let mut nexts: Vec<RefNode> = vec![];
-						let insert_len = nodes.len();
-						// it's insertAfter, increase the insertion index
-						if *position == AfterEnd {
-							index += 1;
-						}
-						// always reset node indexs
-						reset_next_siblings_index(index, &nodes);
-						// split to prev and next
-						if let Some(parent) = &self.borrow_mut().parent {
-							if let Some(parent) = &parent.upgrade() {
-								if let Some(childs) = &mut parent.borrow_mut().childs {
-									// split the nexts for reset index.
-									if index < childs.len() {
-										nexts = childs.split_off(index);
-									}
-									// set node parent
-									for node in &nodes {
-										node.borrow_mut().parent = Some(Rc::downgrade(parent));
-									}
-									// insert nodes at the end
-									childs.extend(nodes);
-								}
-							}
-						}
-						if !nexts.is_empty() {
-							// reset nexts index
-							reset_next_siblings_index(index + insert_len, &nexts);
-							// for borrrow check
-							if let Some(parent) = &self.borrow_mut().parent {
-								if let Some(parent) = parent.upgrade() {
-									if let Some(childs) = &mut parent.borrow_mut().childs {
-										//  insert nodes
-										childs.extend(nexts);
-									}
-								}
-							}
-						}
-					}
-					AfterBegin | BeforeEnd => {
-						// set nodes parent
-						for node in &nodes {
-							node.borrow_mut().parent = Some(Rc::downgrade(self));
-						}
-						// prepend, append
-						if let Some(childs) = &mut self.borrow_mut().childs {
-							if *position == BeforeEnd {
-								// reset nodes index
-								reset_next_siblings_index(childs.len(), &nodes);
-								// append nodes
-								childs.extend(nodes);
-							} else {
-								// always reset nodes index
-								reset_next_siblings_index(0, &nodes);
-								// reset childs index
-								reset_next_siblings_index(nodes.len(), childs);
-								// append childs to nodes
-								nodes.append(childs);
-								// set childs to nodes
-								*childs = nodes;
-							}
-							return;
-						}
-						// reset nodes index
-						reset_next_siblings_index(0, &nodes);
-						// set nodes as childs
-						self.borrow_mut().childs = Some(nodes);
+				cur = ele.parent();
+			}
+			let specified: Box<dyn Any> = node.cloned().to_node();
+			if let Ok(dom) = specified.downcast::<RefNode>() {
+				let nodes = dom.borrow().childs.clone().unwrap_or_default();
+				// keep the child nodes if any of them is not allowed to insert
+				if is_inside {
+					let tag_name = self.tag_names();
+					if nodes.iter().any(|node| !allow_insert(&tag_name, node.borrow().node_type)) {
+						return;
 					}
 				}
+				if nodes.is_empty() {
+					return;
+				}
+				// detach the child nodes
+				dom.borrow_mut().childs = Some(Vec::new());
+				Dom::insert_nodes(self, position, nodes);
 			} else {
 				// not the Dom
 				Dom::halt(
@@ -1110,6 +1343,11 @@ impl Document {
 	}
 	/// Destroy the document
 	pub fn destroy(self) {}
+	// make the created node owned by the document
+	fn own_node<'b>(&self, mut node: Node) -> BoxDynElement<'b> {
+		node.root = Some(Rc::downgrade(&self.doc.borrow().root));
+		Box::new(Rc::new(RefCell::new(node)))
+	}
 }
 
 impl IDocumentTrait for Document {
@@ -1134,6 +1372,42 @@ impl IDocumentTrait for Document {
 			.as_ref()
 			.map(Rc::clone)
 	}
+	// create an element
+	fn create_element<'b>(&self, tag_name: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		if !Dom::is_valid_tag_name(tag_name) {
+			return Err(Box::new(IError::InvalidTagName {
+				name: String::from(tag_name),
+			}));
+		}
+		let name = tag_name.chars().collect::<Vec<char>>();
+		let mut node = Node::new(NodeType::Tag, 0);
+		// the void tags don't allow any child nodes
+		let is_void = !allow_insert(&name, NodeType::Text);
+		if !is_void {
+			let mut end_tag = Node::new(NodeType::TagEnd, 0);
+			end_tag.content = Some(name.clone());
+			node.end_tag = Some(Rc::new(RefCell::new(end_tag)));
+		}
+		let mut meta = TagMeta::default();
+		meta.is_void = is_void;
+		meta.name = name;
+		node.meta = Some(RefCell::new(meta));
+		Ok(self.own_node(node))
+	}
+	// create a text node, the content will be encoded
+	fn create_text_node<'b>(&self, content: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		let content = encode(
+			content.as_bytes(),
+			&EncodeType::NamedOrDecimal,
+			&CharacterSet::Html,
+		);
+		Ok(self.own_node(Node::create_text_node(content.to_chars()?, None)))
+	}
+	// create a comment node
+	fn create_comment<'b>(&self, data: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		let node = Dom::create_data_node(NodeType::Comment, data)?;
+		Ok(self.own_node(node))
+	}
 }
 
 /// Vis: Entry struct of the `mesdoc`'s api.
@@ -1263,8 +1537,176 @@ impl Vis {
 	) -> Elements<'html> {
 		Vis::load_options_catch(html, Vis::options(), handle)
 	}
+	/// Compile a selector, the compiled selector can be reused by the `*_compiled` methods of `Elements`,
+	/// e.g. `find_compiled`, `filter_compiled`, `is_compiled`, so the selector string only needs to be parsed once,
+	/// the nested selectors of `:not()`, `:is()`, `:has()` and so on are also parsed once.
+	///
+	/// Only `find`, `filter`, `is`, `is_all`, `not` and `has` have the compiled methods,
+	/// the traversal methods such as `children`, `siblings`, `next_all`, `parents` and `closest` still take a selector string.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let links = Vis::compile("div.item > a")?;
+	///   for html in ["<div class='item'><a>1</a></div>", "<div class='item'><a>2</a><a>3</a></div>"] {
+	///     let doc = Vis::load(html)?;
+	///     assert!(!doc.find_compiled(&links).is_empty());
+	///   }
+	///   // invalid selectors fail up front
+	///   assert!(Vis::compile("div >").is_err());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn compile(selector: &str) -> Result<CompiledSelector, BoxDynError> {
+		mesdoc::init();
+		Ok(CompiledSelector::new(selector)?)
+	}
+	/// Declare the namespace prefixes for the selectors parsed in the handle, e.g. `svg|rect`, `[xlink|href]`.
+	///
+	/// The prefixes `svg`, `math`, `xlink`, `xml`, `xhtml` and `xmlns` are built in, an undeclared prefix is an invalid selector.
+	/// The empty prefix declares the default namespace of the type selectors and the `*` selector.
+	/// The prefixes are resolved when the selector is compiled, including the nested selectors of `:not()`, `:is()` and `:has()`,
+	/// so a compiled selector keeps the namespaces after the handle returns.
+	/// An element's namespace comes from its prefix and the `xmlns:*` attributes, then the nearest `xmlns` attribute,
+	/// the elements in `svg` and `math` are in the SVG and MathML namespaces, the others are in the XHTML namespace.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <svg><a xlink:href="#logo"><title>logo</title></a></svg>
+	///     <a href="/home"><span title="home">home</span></a>
+	///     <r:item xmlns:r="urn:rss">rss</r:item>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   assert_eq!(doc.find("svg|a").length(), 1);
+	///   assert_eq!(doc.find("*|a").length(), 2);
+	///   assert_eq!(doc.find("[xlink|href='#logo']").length(), 1);
+	///   assert_eq!(doc.find("*|title, [*|title]").length(), 2);
+	///   let items = Vis::with_namespaces(&[("rss", "urn:rss")], || doc.find("rss|item"));
+	///   assert_eq!(items.text(), "rss");
+	///   // the default namespace
+	///   let links = Vis::with_namespaces(&[("", "http://www.w3.org/1999/xhtml")], || doc.find("a"));
+	///   assert_eq!(links.attr("href").unwrap().to_string(), "/home");
+	///   assert!(doc.find("rss|item").is_empty());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn with_namespaces<F, R>(namespaces: &[(&str, &str)], handle: F) -> R
+	where
+		F: FnOnce() -> R,
+	{
+		mesdoc::init();
+		with_namespaces(namespaces, handle)
+	}
+	// create a document fragment with a comment or CDATA node
+	fn create_data_node(node_type: NodeType, data: &str) -> Result<Elements<'static>, BoxDynError> {
+		let mut node = Dom::create_data_node(node_type, data)?;
+		let doc = Vis::parse_doc_with_options("", Vis::options())?;
+		let root = doc.doc.get_root_node();
+		node.parent = Some(Rc::downgrade(&root));
+		node.root = Some(Rc::downgrade(&root));
+		node.document = root.borrow().document.clone();
+		root.borrow_mut().childs = Some(vec![Rc::new(RefCell::new(node))]);
+		Ok(doc.elements())
+	}
+	/// Create a document fragment with a comment node, it can be inserted by the methods such as `prepend`, `append`, `insert_before`.
+	/// Returns an error if the data is not allowed in a comment, e.g. contains `-->`.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let license = Vis::comment(" MIT License ")?;
+	///   assert_eq!(license.outer_html(), "<!-- MIT License -->");
+	///   assert!(Vis::comment("a-->b").is_err());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn comment(data: &str) -> Result<Elements<'static>, BoxDynError> {
+		Vis::create_data_node(NodeType::Comment, data)
+	}
+	/// Create a document fragment with a CDATA node, the CDATA node can only be inserted into the `svg` and `math` elements.
+	/// Returns an error if the data contains `]]>`.
+	pub fn cdata(data: &str) -> Result<Elements<'static>, BoxDynError> {
+		Vis::create_data_node(NodeType::XMLCDATA, data)
+	}
 	/// return an elements collection from an BoxDynElement
 	pub fn dom<'b>(ele: &BoxDynElement) -> Elements<'b> {
 		Elements::with_nodes(vec![ele.cloned()])
 	}
+	/// Register a custom pseudo selector, the handle checks the elements one by one.
+	///
+	/// The selector can take parameters with the patterns `{spaces}`, `{identity}`, `{nth}`, `{selector}` and `{regexp#...#}`,
+	/// the matched patterns will be passed to the handle in order, the raw characters and spaces are ignored.
+	/// The pseudo selector is registered globally, it will be matched before the built-in selectors.
+	/// Return an error if the name is already registered or the selector's patterns are not written correctly, e.g. an unclosed `{`.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   Vis::register_pseudo(r##":price-over({spaces}{regexp#(\d+)#}{spaces})"##, |ele, params| {
+	///     let min = params[0].value.parse::<f64>().unwrap_or(0.0);
+	///     ele.text().trim().trim_start_matches('$').parse::<f64>().map_or(false, |price| price > min)
+	///   })?;
+	///   let html = r##"
+	///     <ul>
+	///       <li class="price">$12.5</li>
+	///       <li class="price">$30</li>
+	///       <li class="price">unknown</li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let prices = doc.find(".price:price-over(20)");
+	///   assert_eq!(prices.length(), 1);
+	///   assert_eq!(prices.text(), "$30");
+	///   // the name is already registered
+	///   assert!(Vis::register_pseudo(":price-over", |_, _| true).is_err());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn register_pseudo<F>(selector: &str, handle: F) -> Result<(), BoxDynError>
+	where
+		F: Fn(&dyn IElementTrait, &[PseudoParam]) -> bool + Send + Sync + 'static,
+	{
+		mesdoc::init();
+		add_pseudo(selector, PseudoHandle::One(Arc::new(handle)))?;
+		Ok(())
+	}
+	/// Register a custom pseudo selector, the handle filters the whole elements collection at once.
+	///
+	/// The handle should return the matched elements in the order of the collection,
+	/// the parameters are same as the method `register_pseudo`.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   // keep the elements except the first one
+	///   Vis::register_pseudo_all(":skip-first", |eles, _| eles.slice(1..))?;
+	///   let html = r##"
+	///     <ul>
+	///       <li>item1</li>
+	///       <li>item2</li>
+	///       <li>item3</li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li").filter(":skip-first");
+	///   assert_eq!(items.length(), 2);
+	///   assert_eq!(items.text(), "item2item3");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn register_pseudo_all<F>(selector: &str, handle: F) -> Result<(), BoxDynError>
+	where
+		F: for<'r> Fn(&Elements<'r>, &[PseudoParam]) -> Elements<'r> + Send + Sync + 'static,
+	{
+		mesdoc::init();
+		add_pseudo(selector, PseudoHandle::All(Arc::new(handle)))?;
+		Ok(())
+	}
 }
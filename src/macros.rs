@@ -24,3 +24,12 @@ macro_rules! cfg_feat_mutation {
     )*
   };
 }
+
+macro_rules! cfg_feat_serde {
+	($($item:item)*) => {
+    $(
+      #[cfg(feature = "serde")]
+      $item
+    )*
+  };
+}
@@ -20,8 +20,37 @@ pub const NAME_SELECTOR_ATTR: &str = "attr";
 pub const NAME_SELECTOR_NAME: &str = "name";
 pub const NAME_SELECTOR_CLASS: &str = "class";
 pub const NAME_SELECTOR_ID: &str = "id";
+pub const NAME_SELECTOR_NS_NAME: &str = "ns-name";
+pub const NAME_SELECTOR_NS_ATTR: &str = "ns-attr";
+// namespaces
+pub const NAMESPACE_HTML: &str = "http://www.w3.org/1999/xhtml";
+pub const NAMESPACE_SVG: &str = "http://www.w3.org/2000/svg";
+pub const NAMESPACE_MATHML: &str = "http://www.w3.org/1998/Math/MathML";
+pub const NAMESPACE_XLINK: &str = "http://www.w3.org/1999/xlink";
+pub const NAMESPACE_XML: &str = "http://www.w3.org/XML/1998/namespace";
+pub const NAMESPACE_XMLNS: &str = "http://www.w3.org/2000/xmlns/";
+// the built-in namespace prefixes, they can be used without declaring
+pub const BUILTIN_NAMESPACES: [(&str, &str); 6] = [
+	("xhtml", NAMESPACE_HTML),
+	("svg", NAMESPACE_SVG),
+	("math", NAMESPACE_MATHML),
+	("xlink", NAMESPACE_XLINK),
+	("xml", NAMESPACE_XML),
+	("xmlns", NAMESPACE_XMLNS),
+];
 // alias selectors
 pub const SELECTOR_ALIAS_NAME_HEADER: (&str, &str) = (":header", "h1,h2,h3,h4,h5,h6");
 pub const SELECTOR_ALIAS_NAME_SUBMIT: (&str, &str) =
 	(":submit", "input[type='submit'],button[type='submit']");
 pub const SELECTOR_ALIAS_NAME_INPUT: (&str, &str) = (":input", "input,select,textarea,button");
+// input type alias selectors, the input without type is a text input
+pub const SELECTOR_ALIAS_NAME_TEXT: (&str, &str) =
+	(":text", "input:not([type]),input[type='text' i]");
+pub const SELECTOR_ALIAS_NAME_PASSWORD: (&str, &str) = (":password", "input[type='password' i]");
+pub const SELECTOR_ALIAS_NAME_RADIO: (&str, &str) = (":radio", "input[type='radio' i]");
+pub const SELECTOR_ALIAS_NAME_CHECKBOX: (&str, &str) = (":checkbox", "input[type='checkbox' i]");
+pub const SELECTOR_ALIAS_NAME_FILE: (&str, &str) = (":file", "input[type='file' i]");
+pub const SELECTOR_ALIAS_NAME_IMAGE: (&str, &str) = (":image", "input[type='image' i]");
+pub const SELECTOR_ALIAS_NAME_BUTTON: (&str, &str) = (":button", "button,input[type='button' i]");
+pub const SELECTOR_ALIAS_NAME_RESET: (&str, &str) =
+	(":reset", "input[type='reset' i],button[type='reset' i]");
@@ -8,4 +8,12 @@ pub enum Error {
 	MethodOnInvalidSelector { method: String, error: String },
 	#[error("Call method '{method}' cause an error: {message}")]
 	InvalidTraitMethodCall { method: String, message: String },
+	#[error(
+		"Invalid extract source:'{context}', expect 'text', 'html', 'outer_html', 'attr:NAME' or 'val'"
+	)]
+	InvalidExtractSource { context: String },
+	#[error("Invalid tag name:'{name}'")]
+	InvalidTagName { name: String },
+	#[error("Invalid node data:'{data}'<{reason}>")]
+	InvalidNodeData { data: String, reason: String },
 }
@@ -0,0 +1,200 @@
+use rphtml::entity::decode_chars;
+use std::fmt;
+
+/// The value of a `data-*` attribute, parsed the way jQuery's `data` method does.
+/// The `Json` variant only exists with the `serde` feature, so the enum is non exhaustive.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DataValue {
+	/// `null`
+	Null,
+	/// `true` or `false`
+	Bool(bool),
+	/// the number that keeps the same text after converted back, e.g. `12.5`, `1e+21`, but not `1.0`, `007`, `-0` or `1e21`
+	Number(f64),
+	/// the other values
+	Text(String),
+	/// the value wrapped by `{}` or `[]`, and can be parsed as json
+	#[cfg(feature = "serde")]
+	Json(serde_json::Value),
+}
+
+impl DataValue {
+	/// Parse the attribute value, the html entities should be decoded.
+	pub fn parse(value: &str) -> Self {
+		match value {
+			"true" => return DataValue::Bool(true),
+			"false" => return DataValue::Bool(false),
+			"null" => return DataValue::Null,
+			_ => {}
+		}
+		if let Ok(num) = value.parse::<f64>() {
+			if to_js_number(num).is_some_and(|text| text == value) {
+				return DataValue::Number(num);
+			}
+		}
+		#[cfg(feature = "serde")]
+		{
+			let is_json = (value.starts_with('{') && value.ends_with('}'))
+				|| (value.starts_with('[') && value.ends_with(']'));
+			if is_json {
+				if let Ok(json) = serde_json::from_str(value) {
+					return DataValue::Json(json);
+				}
+			}
+		}
+		DataValue::Text(String::from(value))
+	}
+	/// Get the text of the value, `Null` is `None`.
+	pub fn as_text(&self) -> Option<String> {
+		match self {
+			DataValue::Null => None,
+			DataValue::Text(text) => Some(text.clone()),
+			value => Some(value.to_string()),
+		}
+	}
+	/// Get the number of the value.
+	pub fn as_number(&self) -> Option<f64> {
+		match self {
+			DataValue::Number(num) => Some(*num),
+			_ => None,
+		}
+	}
+	/// Get the bool of the value.
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			DataValue::Bool(value) => Some(*value),
+			_ => None,
+		}
+	}
+	/// Check if the value is null.
+	pub fn is_null(&self) -> bool {
+		matches!(self, DataValue::Null)
+	}
+	cfg_feat_serde! {
+		/// Get the json of the value.
+		pub fn as_json(&self) -> Option<&serde_json::Value> {
+			match self {
+				DataValue::Json(json) => Some(json),
+				_ => None,
+			}
+		}
+	}
+}
+
+/// The attribute value of the data, the json is serialized.
+impl fmt::Display for DataValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DataValue::Null => f.write_str("null"),
+			DataValue::Bool(value) => write!(f, "{}", value),
+			DataValue::Number(num) => match to_js_number(*num) {
+				Some(text) => f.write_str(&text),
+				None => write!(f, "{}", num),
+			},
+			DataValue::Text(text) => f.write_str(text),
+			#[cfg(feature = "serde")]
+			DataValue::Json(json) => write!(f, "{}", json),
+		}
+	}
+}
+
+impl From<&str> for DataValue {
+	fn from(value: &str) -> Self {
+		DataValue::Text(String::from(value))
+	}
+}
+
+impl From<String> for DataValue {
+	fn from(value: String) -> Self {
+		DataValue::Text(value)
+	}
+}
+
+impl From<bool> for DataValue {
+	fn from(value: bool) -> Self {
+		DataValue::Bool(value)
+	}
+}
+
+impl From<f64> for DataValue {
+	fn from(value: f64) -> Self {
+		DataValue::Number(value)
+	}
+}
+
+impl From<i32> for DataValue {
+	fn from(value: i32) -> Self {
+		DataValue::Number(value as f64)
+	}
+}
+
+impl From<i64> for DataValue {
+	fn from(value: i64) -> Self {
+		DataValue::Number(value as f64)
+	}
+}
+
+cfg_feat_serde! {
+	impl From<serde_json::Value> for DataValue {
+		fn from(value: serde_json::Value) -> Self {
+			DataValue::Json(value)
+		}
+	}
+}
+
+// write the number the same as javascript, `-0` is written as `0`,
+// the numbers out of the range are written with exponent, e.g. `1e+21`, `1.5e-7`,
+// `NaN` and infinite numbers are `None`
+fn to_js_number(num: f64) -> Option<String> {
+	if !num.is_finite() {
+		return None;
+	}
+	if num == 0.0 {
+		return Some(String::from("0"));
+	}
+	if (1e-6..1e21).contains(&num.abs()) {
+		return Some(num.to_string());
+	}
+	let text = format!("{:e}", num);
+	Some(match text.split_once('e') {
+		Some((digits, exp)) if !exp.starts_with('-') => format!("{}e+{}", digits, exp),
+		_ => text,
+	})
+}
+
+// decode the html entities in the attribute value, e.g. `&quot;`
+pub(crate) fn decode_attr_value(value: &str) -> String {
+	let chars = value.chars().collect::<Vec<char>>();
+	decode_chars(&chars).iter().collect()
+}
+
+// the data key of the attribute name, e.g. `data-foo-bar` to `fooBar`
+pub(crate) fn to_data_key(attr_name: &str) -> Option<String> {
+	let name = attr_name.to_ascii_lowercase();
+	let name = name.strip_prefix("data-")?;
+	let mut key = String::with_capacity(name.len());
+	let mut chars = name.chars().peekable();
+	while let Some(ch) = chars.next() {
+		match chars.peek() {
+			Some(next) if ch == '-' && next.is_ascii_lowercase() => {
+				key.push(next.to_ascii_uppercase());
+				chars.next();
+			}
+			_ => key.push(ch),
+		}
+	}
+	Some(key)
+}
+
+// the attribute name of the data key, e.g. `fooBar` and `foo-bar` to `data-foo-bar`
+pub(crate) fn to_data_attr_name(key: &str) -> String {
+	let mut attr_name = String::from("data-");
+	for ch in key.chars() {
+		if ch.is_ascii_uppercase() {
+			attr_name.push('-');
+		}
+		attr_name.push(ch.to_ascii_lowercase());
+	}
+	attr_name
+}
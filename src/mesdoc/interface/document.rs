@@ -1,5 +1,5 @@
 use super::{BoxDynElement, BoxDynNode, Elements};
-use crate::mesdoc::error::BoxDynError;
+use crate::mesdoc::error::{BoxDynError, Error as IError};
 use std::rc::Rc;
 
 pub type MaybeDoc<'a> = Option<Box<dyn IDocumentTrait + 'a>>;
@@ -48,6 +48,42 @@ pub trait IDocumentTrait {
 	fn onerror(&self) -> Option<Rc<IErrorHandle>> {
 		None
 	}
+	/// Create an element owned by the document, the element is not inserted into the document.
+	/// Returns an error if the tag name is invalid, or the document doesn't support creating nodes.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let doc = Vis::load("<div id='content'></div>")?;
+	///   let document = doc.document().unwrap();
+	///   let link = document.create_element("a")?;
+	///   assert_eq!(link.outer_html(), "<a></a>");
+	///   assert!(link.owner_document().is_some());
+	///   assert!(document.create_element("<a>").is_err());
+	///   Ok(())
+	/// }
+	/// ```
+	fn create_element<'b>(&self, _tag_name: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: String::from("create_element"),
+			message: String::from("The document doesn't support creating nodes"),
+		}))
+	}
+	/// Create a text node owned by the document, the content is encoded when the node is rendered as html.
+	fn create_text_node<'b>(&self, _content: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: String::from("create_text_node"),
+			message: String::from("The document doesn't support creating nodes"),
+		}))
+	}
+	/// Create a comment node owned by the document, returns an error if the data is not allowed in a comment, e.g. contains `-->`.
+	fn create_comment<'b>(&self, _data: &str) -> Result<BoxDynElement<'b>, BoxDynError> {
+		Err(Box::new(IError::InvalidTraitMethodCall {
+			method: String::from("create_comment"),
+			message: String::from("The document doesn't support creating nodes"),
+		}))
+	}
 	// trigger error
 	fn trigger_error(&self, error: BoxDynError) {
 		if let Some(handle) = &self.onerror() {
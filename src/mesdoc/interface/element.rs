@@ -312,6 +312,19 @@ pub trait IElementTrait: INodeTrait {
 	// append child, insert before
 	cfg_feat_insertion! {
 		fn insert_adjacent(&mut self, position: &InsertPosition, node: &BoxDynElement);
+		/// Move all the child nodes of the node to the position, the node will be empty.
+		fn insert_adjacent_child_nodes(&mut self, _position: &InsertPosition, _node: &BoxDynElement) {
+			if let Some(doc) = &self.owner_document() {
+				doc.trigger_error(Box::new(IError::InvalidTraitMethodCall {
+					method: "insert_adjacent_child_nodes".into(),
+					message: "The insert_adjacent_child_nodes method is not implemented.".into(),
+				}));
+			}
+		}
+		// check if the node can be inserted into the element, e.g. `<title>` only allows the text nodes
+		fn allow_child(&self, _node: &BoxDynElement) -> bool {
+			true
+		}
 		fn replace_with(&mut self, node: &BoxDynElement);
 	}
 	cfg_feat_mutation! {
@@ -5,20 +5,22 @@ cfg_feat_text! {
 cfg_feat_insertion! {
 	use super::InsertPosition;
 }
-use super::{BoxDynElement, IAttrValue, IElementTrait, IFormValue, MaybeDoc};
+use super::data::{decode_attr_value, to_data_attr_name, to_data_key};
+use super::{BoxDynElement, DataValue, IAttrValue, IElementTrait, IFormValue, MaybeDoc, Nodes};
 use crate::mesdoc::error::BoxDynError;
 use crate::mesdoc::{constants::ATTR_CLASS, error::Error as IError, utils::class_list_to_string};
 use crate::mesdoc::{
 	constants::DEF_NODES_LEN,
 	selector::{
 		rule::{MatchAllHandle, MatchOneHandle},
-		Combinator, QueryProcess, Selector, SelectorSegment,
+		Combinator, CompiledSelector, QueryProcess, Selector, SelectorSegment,
 	},
 };
 use crate::mesdoc::{
 	selector::rule::MatchSpecifiedHandle,
 	utils::{get_class_list, retain_by_index},
 };
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::{
@@ -1103,6 +1105,30 @@ impl<'a> Elements<'a> {
 		Elements::new()
 	}
 
+	/// Get the descendants of each element in the Elements, filtered by a compiled selector.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let links = Vis::compile("li > a")?;
+	///   assert_eq!(doc.find_compiled(&links).length(), 2);
+	///   assert_eq!(doc.find(".item1, .item2").find_compiled(&Vis::compile("a")?).text(), "item1");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn find_compiled(&self, selector: &CompiledSelector) -> Elements<'a> {
+		self.find_selector(&selector.lookup)
+	}
+
 	/// Reduce the Elements to those that match the selector.
 	///
 	/// ```
@@ -1135,9 +1161,35 @@ impl<'a> Elements<'a> {
 	/// ```
 	pub fn filter(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "filter";
-		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_type_handle(selector, &FilterType::Filter).0
-		})
+		self.trigger_method(METHOD, selector, |selector| self.filter_selector(selector))
+	}
+
+	/// Reduce the Elements to those that match the compiled selector.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li");
+	///   let item = Vis::compile(".item2, .item3")?;
+	///   assert_eq!(items.filter_compiled(&item).text(), "item2item3");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn filter_compiled(&self, selector: &CompiledSelector) -> Elements<'a> {
+		self.filter_selector(&selector.filter)
+	}
+
+	pub(crate) fn filter_selector(&self, selector: &Selector) -> Elements<'a> {
+		self.filter_type_handle(selector, &FilterType::Filter).0
 	}
 
 	/// Reduce the Elements to those that pass the handle function test.
@@ -1262,6 +1314,41 @@ impl<'a> Elements<'a> {
 		self.select_with_comb("children", selector, Combinator::Children)
 	}
 
+	/// Get the child nodes of each element in Elements, include the text, comment and CDATA nodes, in the document order.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::{BoxDynError, INodeType};
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <html>
+	///       <head>
+	///         <title>document</title>
+	///       </head>
+	///       <body>
+	///         <div id="content">Hello,<!--comment--><b>visdom</b>!</div>
+	///       </body>
+	///     </html>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let contents = doc.find("#content").contents();
+	///   assert_eq!(contents.length(), 4);
+	///   let comments = contents.filter_type(INodeType::Comment);
+	///   assert_eq!(comments.length(), 1);
+	///   assert_eq!(comments.get(0).unwrap().text(), "comment");
+	///   assert_eq!(contents.filter_type(INodeType::Text).length(), 2);
+	///   assert_eq!(contents.elements().text(), "visdom");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn contents(&self) -> Nodes<'a> {
+		let mut result = Nodes::with_capacity(DEF_NODES_LEN);
+		for ele in self.get_ref() {
+			result.get_mut_ref().extend(ele.child_nodes());
+		}
+		result
+	}
+
 	/// Get the previous sibling of each element in Elements, when the selector is not empty, will filtered by the selector.
 	///
 	/// ```
@@ -1991,6 +2078,30 @@ impl<'a> Elements<'a> {
 		})
 	}
 
+	/// Check at least one element in Elements is matched by the compiled selector.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li");
+	///   assert!(items.is_compiled(&Vis::compile(".item2")?));
+	///   assert!(!items.is_compiled(&Vis::compile("a")?));
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn is_compiled(&self, selector: &CompiledSelector) -> bool {
+		self.filter_type_handle(&selector.filter, &FilterType::Is).1
+	}
+
 	/// Check at least one element in Elements call the handle function return true.
 	///
 	/// ```
@@ -2113,6 +2224,32 @@ impl<'a> Elements<'a> {
 		})
 	}
 
+	/// Check if each element in Elements are all matched by the compiled selector.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li");
+	///   assert!(items.is_all_compiled(&Vis::compile("ul > li")?));
+	///   assert!(!items.is_all_compiled(&Vis::compile(".item2")?));
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn is_all_compiled(&self, selector: &CompiledSelector) -> bool {
+		self
+			.filter_type_handle(&selector.filter, &FilterType::IsAll)
+			.1
+	}
+
 	/// Check if each element in Elements call the handle function are all returned true.
 	///
 	/// ```
@@ -2233,9 +2370,35 @@ impl<'a> Elements<'a> {
 	/// ```
 	pub fn not(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "not";
-		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_type_handle(selector, &FilterType::Not).0
-		})
+		self.trigger_method(METHOD, selector, |selector| self.not_selector(selector))
+	}
+
+	/// Remove elements those that match the compiled selector from the Elements set.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li");
+	///   let item = Vis::compile(".item2, .item3")?;
+	///   assert_eq!(items.not_compiled(&item).text(), "item1");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn not_compiled(&self, selector: &CompiledSelector) -> Elements<'a> {
+		self.not_selector(&selector.filter)
+	}
+
+	pub(crate) fn not_selector(&self, selector: &Selector) -> Elements<'a> {
+		self.filter_type_handle(selector, &FilterType::Not).0
 	}
 
 	/// Remove elements those that pass the handle function test from the Elements set.
@@ -2316,6 +2479,7 @@ impl<'a> Elements<'a> {
 	}
 
 	/// Reduce Elements to those that have a descendant that matches the selector.
+	/// The selector can also be a relative selector that starts with a combinator `>`, `+` or `~`.
 	///
 	/// ```
 	/// use visdom::Vis;
@@ -2341,11 +2505,43 @@ impl<'a> Elements<'a> {
 	///   let items = dl.children("");
 	///   assert_eq!(items.filter("dt").text(), "<Title>");
 	///   assert_eq!(items.filter("dd").text(), "item1item2item3");
+	///   assert_eq!(items.has("span").text(), "item1");
+	///   assert_eq!(items.has("> strong").text(), "<Title>");
+	///   assert_eq!(items.has("+ .item2").text(), "item1");
+	///   assert_eq!(items.has("~ .item3").length(), 3);
 	///   Ok(())
 	/// }
 	/// ```
 	pub fn has(&self, selector: &str) -> Elements<'a> {
 		const METHOD: &str = "has";
+		self.trigger_method(METHOD, selector, |selector| self.has_selector(selector))
+	}
+
+	/// Reduce Elements to those that have a descendant that matches the compiled selector.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <ul>
+	///       <li class="item1"><a href="#">item1</a></li>
+	///       <li class="item2">item2</li>
+	///       <li class="item3"><a href="#">item3</a></li>
+	///     </ul>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let items = doc.find("li");
+	///   assert_eq!(items.has_compiled(&Vis::compile("a")?).length(), 2);
+	///   assert_eq!(items.has_compiled(&Vis::compile("+ .item3")?).text(), "item2");
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn has_compiled(&self, selector: &CompiledSelector) -> Elements<'a> {
+		self.has_selector(&selector.filter)
+	}
+
+	pub(crate) fn has_selector(&self, selector: &Selector) -> Elements<'a> {
 		fn loop_handle(ele: &BoxDynElement, selector: &Selector) -> bool {
 			let childs = ele.children();
 			if !childs.is_empty() {
@@ -2361,9 +2557,11 @@ impl<'a> Elements<'a> {
 			}
 			false
 		}
-		self.trigger_method(METHOD, selector, |selector| {
-			self.filter_by(|_, ele| loop_handle(ele, selector))
-		})
+		if selector.is_relative() {
+			// relative selectors, find from the element itself with the head combinators
+			return self.filter_by(|_, ele| !Elements::with_node(ele).find_selector(selector).is_empty());
+		}
+		self.filter_by(|_, ele| loop_handle(ele, selector))
 	}
 
 	/// Reduce Elements to those that have a descendant that matches the selector.
@@ -3300,6 +3498,103 @@ impl<'a> Elements<'a> {
 		self
 	}
 
+	/// Get the `data-*` attribute of the first element in Elements, the key can be camel case or dashed, e.g. `fooBar` or `foo-bar` for `data-foo-bar`.
+	/// The value is parsed as jQuery does: `true`, `false`, `null`, the numbers and the json(with the `serde` feature), otherwise it's a text.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::{BoxDynError, DataValue};
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <html>
+	///       <head>
+	///         <title>document</title>
+	///       </head>
+	///       <body>
+	///         <div class="product" data-price="12.5" data-in-stock="true" data-sku="007" data-note="a &amp; b"></div>
+	///       </body>
+	///     </html>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let product = doc.find(".product");
+	///   assert_eq!(product.data("price"), Some(DataValue::Number(12.5)));
+	///   assert_eq!(product.data("inStock"), Some(DataValue::Bool(true)));
+	///   assert_eq!(product.data("in-stock"), Some(DataValue::Bool(true)));
+	///   assert_eq!(product.data("sku"), Some(DataValue::Text(String::from("007"))));
+	///   assert_eq!(product.data("note").unwrap().as_text().unwrap(), "a & b");
+	///   assert!(product.data("color").is_none());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn data(&self, key: &str) -> Option<DataValue> {
+		let value = self.attr(&to_data_attr_name(key))?;
+		Some(DataValue::parse(&decode_attr_value(&value.to_string())))
+	}
+
+	/// Get all the `data-*` attributes of the first element in Elements, the keys are camel case, e.g. `fooBar` for `data-foo-bar`.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::{BoxDynError, DataValue};
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"<div class="product" id="p1" data-price="12.5" data-in-stock="false" data-tag></div>"##;
+	///   let doc = Vis::load(html)?;
+	///   let dataset = doc.find(".product").dataset();
+	///   assert_eq!(dataset.len(), 3);
+	///   assert_eq!(dataset["price"], DataValue::Number(12.5));
+	///   assert_eq!(dataset["inStock"], DataValue::Bool(false));
+	///   assert_eq!(dataset["tag"], DataValue::Text(String::new()));
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn dataset(&self) -> BTreeMap<String, DataValue> {
+		let mut dataset = BTreeMap::new();
+		if let Some(ele) = self.get(0) {
+			for (name, value) in ele.get_attributes() {
+				if let Some(key) = to_data_key(&name) {
+					let value = DataValue::parse(&decode_attr_value(&value.to_string()));
+					dataset.insert(key, value);
+				}
+			}
+		}
+		dataset
+	}
+
+	/// Set the `data-*` attribute of each element in Elements, the value is written as the text that `data` method can parse back.
+	/// The large and small numbers are written with exponent like javascript, e.g. `1e+21`, `1e-7`.
+	/// The `NaN` and infinite numbers can't be parsed back, they are ignored and the attribute is not changed.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::{BoxDynError, DataValue};
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"<div class="product"></div>"##;
+	///   let doc = Vis::load(html)?;
+	///   let mut product = doc.find(".product");
+	///   product.set_data("price", 12.5).set_data("inStock", true).set_data("name", "apple");
+	///   assert_eq!(product.attr("data-in-stock").unwrap().to_string(), "true");
+	///   assert_eq!(product.data("price"), Some(DataValue::Number(12.5)));
+	///   assert_eq!(product.data("name"), Some(DataValue::Text(String::from("apple"))));
+	///   product.remove_data("price");
+	///   assert!(product.data("price").is_none());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn set_data(&mut self, key: &str, value: impl Into<DataValue>) -> &mut Self {
+		let value = value.into();
+		if value.as_number().is_some_and(|num| !num.is_finite()) {
+			return self;
+		}
+		// the value is decoded when it's read, so encode the `&`
+		let value = value.to_string().replace('&', "&amp;");
+		self.set_attr(&to_data_attr_name(key), Some(&value))
+	}
+
+	/// Remove the `data-*` attribute of each element in Elements.
+	pub fn remove_data(&mut self, key: &str) -> &mut Self {
+		self.remove_attr(&to_data_attr_name(key))
+	}
+
 	/// Check if Elements's ClassList contains the specified class name, multiple classes can be splitted by whitespaces.
 	///
 	/// ```
@@ -3841,6 +4136,159 @@ impl<'a> Elements<'a> {
 			self.replace(elements);
 			self
 		}
+		// the wrapper element, the first element of the wrapper Elements or the first child element of the document fragment
+		fn get_wrapper<'b>(wrapper: &Elements) -> Option<BoxDynElement<'b>> {
+			let first = wrapper.get_ref().first()?;
+			if first.is_root_element() {
+				return first.children().get_ref().first().map(|ele| ele.cloned());
+			}
+			Some(first.cloned())
+		}
+		// the innermost element of the wrapper, find the first child element deeply
+		fn get_innermost<'b>(wrapper: &BoxDynElement) -> BoxDynElement<'b> {
+			let mut cur = wrapper.cloned();
+			loop {
+				let first_child = cur.children().get_ref().first().map(|ele| ele.cloned());
+				match first_child {
+					Some(child) => cur = child,
+					None => return cur,
+				}
+			}
+		}
+		/// Wrap each element in the Elements set with a copy of the wrapper,
+		/// the wrapper is the first element of the parameter Elements, or the first child element of a loaded html fragment.
+		/// If the wrapper has child elements, the element will be moved into the innermost first child element.
+		/// The element that is not allowed in the wrapper, e.g. an element in `<title>`, will keep in its place.
+		///
+		/// ```
+		/// use visdom::Vis;
+		/// use visdom::types::BoxDynError;
+		/// fn main()-> Result<(), BoxDynError>{
+		///   let html = r##"<div class="content"><table id="t1"></table><table id="t2"></table></div>"##;
+		///   let doc = Vis::load(html)?;
+		///   let mut tables = doc.find("table");
+		///   tables.wrap(&Vis::load(r#"<div class="scroll"><div class="inner"></div></div>"#)?);
+		///   assert_eq!(doc.find(".scroll").length(), 2);
+		///   assert_eq!(doc.find(".scroll > .inner > table").length(), 2);
+		///   assert_eq!(tables.parent("").attr("class").unwrap().to_string(), "inner");
+		///   Ok(())
+		/// }
+		/// ```
+		pub fn wrap(&mut self, wrapper: &Elements) -> &mut Self {
+			let wrapper = match Elements::get_wrapper(wrapper) {
+				Some(wrapper) => wrapper,
+				None => return self,
+			};
+			for ele in self.get_mut_ref() {
+				let wrap = wrapper.copied();
+				let mut innermost = Elements::get_innermost(&wrap);
+				// the element is not allowed in the wrapper, keep it in its place
+				if !innermost.allow_child(ele) {
+					continue;
+				}
+				ele.insert_adjacent(&InsertPosition::BeforeBegin, &wrap);
+				innermost.insert_adjacent(&InsertPosition::BeforeEnd, ele);
+			}
+			self
+		}
+		/// Wrap all the elements in the Elements set with a copy of the wrapper,
+		/// the wrapper is inserted before the first element, then all the elements are moved into it in order.
+		/// The elements that are not allowed in the wrapper keep in their place, the wrapper is not inserted if no element is moved.
+		///
+		/// ```
+		/// use visdom::Vis;
+		/// use visdom::types::BoxDynError;
+		/// fn main()-> Result<(), BoxDynError>{
+		///   let html = r##"<div class="content"><h3>title</h3><p>a</p><span>-</span><p>b</p></div>"##;
+		///   let doc = Vis::load(html)?;
+		///   doc.find("p").wrap_all(&Vis::load(r#"<section class="body"></section>"#)?);
+		///   assert_eq!(doc.find(".content").children("").length(), 3);
+		///   assert_eq!(doc.find("section.body").html(), "<p>a</p><p>b</p>");
+		///   assert_eq!(doc.find("section.body").prev("").text(), "title");
+		///   Ok(())
+		/// }
+		/// ```
+		pub fn wrap_all(&mut self, wrapper: &Elements) -> &mut Self {
+			let wrapper = match Elements::get_wrapper(wrapper) {
+				Some(wrapper) => wrapper,
+				None => return self,
+			};
+			let wrap = wrapper.copied();
+			let mut innermost = Elements::get_innermost(&wrap);
+			// the elements not allowed in the wrapper keep in their place
+			let mut allowed = self
+				.get_ref()
+				.iter()
+				.filter(|ele| innermost.allow_child(ele))
+				.map(|ele| ele.cloned())
+				.collect::<Vec<BoxDynElement>>();
+			// no element can be moved, don't insert the empty wrapper
+			if let Some(first) = allowed.first_mut() {
+				first.insert_adjacent(&InsertPosition::BeforeBegin, &wrap);
+				for ele in &allowed {
+					innermost.insert_adjacent(&InsertPosition::BeforeEnd, ele);
+				}
+			}
+			self
+		}
+		/// Wrap the child nodes of each element in the Elements set with a copy of the wrapper, include the text nodes.
+		/// The child nodes that are not allowed in the wrapper, e.g. the elements in `<textarea>`, will keep in their place.
+		///
+		/// ```
+		/// use visdom::Vis;
+		/// use visdom::types::BoxDynError;
+		/// fn main()-> Result<(), BoxDynError>{
+		///   let html = r##"<div class="content">bare <b>text</b></div><div class="content"></div>"##;
+		///   let doc = Vis::load(html)?;
+		///   let mut contents = doc.find(".content");
+		///   contents.wrap_inner(&Vis::load("<p></p>")?);
+		///   assert_eq!(contents.eq(0).html(), "<p>bare <b>text</b></p>");
+		///   assert_eq!(contents.eq(1).html(), "<p></p>");
+		///   Ok(())
+		/// }
+		/// ```
+		pub fn wrap_inner(&mut self, wrapper: &Elements) -> &mut Self {
+			let wrapper = match Elements::get_wrapper(wrapper) {
+				Some(wrapper) => wrapper,
+				None => return self,
+			};
+			for ele in self.get_mut_ref() {
+				let wrap = wrapper.copied();
+				let mut innermost = Elements::get_innermost(&wrap);
+				innermost.insert_adjacent_child_nodes(&InsertPosition::BeforeEnd, ele);
+				if ele.child_nodes_length() == 0 {
+					ele.insert_adjacent(&InsertPosition::BeforeEnd, &wrap);
+				}
+			}
+			self
+		}
+		/// Remove the parents of the elements in the Elements set, keep their child nodes in the place.
+		/// The `html` and `body` elements are not removed.
+		///
+		/// ```
+		/// use visdom::Vis;
+		/// use visdom::types::BoxDynError;
+		/// fn main()-> Result<(), BoxDynError>{
+		///   let html = r##"<p><font color="red"><span>a</span>b</font> <font><span>c</span></font></p>"##;
+		///   let doc = Vis::load(html)?;
+		///   doc.find("font > span").unwrap();
+		///   assert_eq!(doc.find("p").html(), "<span>a</span>b <span>c</span>");
+		///   Ok(())
+		/// }
+		/// ```
+		pub fn unwrap(&mut self) -> &mut Self {
+			for parent in self.parent("").get_mut_ref() {
+				if parent.is_root_element() || matches!(parent.tag_name().as_str(), "HTML" | "BODY") {
+					continue;
+				}
+				let cur = parent.cloned();
+				parent.insert_adjacent_child_nodes(&InsertPosition::BeforeBegin, &cur);
+				if let Some(mut grand_parent) = parent.parent() {
+					grand_parent.remove_child(cur);
+				}
+			}
+			self
+		}
 	}
 }
 
@@ -24,3 +24,9 @@ cfg_feat_text! {
 // elements
 mod elements;
 pub use elements::Elements;
+// nodes
+mod nodes;
+pub use nodes::Nodes;
+// data
+mod data;
+pub use data::DataValue;
@@ -1,6 +1,6 @@
 use super::{BoxDynElement, BoxDynText, BoxDynUncareNode, MaybeDoc, MaybeElement};
 use std::any::Any;
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum INodeType {
 	Element = 1,
 	Text = 3,
@@ -38,6 +38,12 @@ impl<'a> IEnumTyped<'a> {
 			_ => None,
 		}
 	}
+	pub fn into_uncare_node(self) -> Option<BoxDynUncareNode<'a>> {
+		match self {
+			IEnumTyped::UncareNode(ele) => Some(ele),
+			_ => None,
+		}
+	}
 }
 
 pub trait INodeTrait {
@@ -79,6 +85,10 @@ pub trait INodeTrait {
 	fn set_html(&mut self, content: &str);
 	// node index
 	fn index(&self) -> usize;
+	// node id, unique while the node is alive, can be used as a cache key, `None` means the node can't be cached
+	fn node_id(&self) -> Option<usize> {
+		None
+	}
 }
 
 #[cfg(test)]
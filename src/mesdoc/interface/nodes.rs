@@ -0,0 +1,106 @@
+use super::{BoxDynNode, Elements, INodeType};
+cfg_feat_text! {
+	use super::Texts;
+}
+
+/// A collection of the nodes with different node types, e.g. the element, text, comment and CDATA nodes,
+/// the nodes are kept in the document order.
+pub struct Nodes<'a> {
+	nodes: Vec<BoxDynNode<'a>>,
+}
+
+impl<'a> Nodes<'a> {
+	pub fn with_capacity(cap: usize) -> Self {
+		Nodes {
+			nodes: Vec::with_capacity(cap),
+		}
+	}
+	pub fn length(&self) -> usize {
+		self.nodes.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.length() == 0
+	}
+	// get ref
+	pub fn get_ref(&self) -> &Vec<BoxDynNode<'a>> {
+		&self.nodes
+	}
+	// get mut ref
+	pub fn get_mut_ref(&mut self) -> &mut Vec<BoxDynNode<'a>> {
+		&mut self.nodes
+	}
+	// get the node by index
+	pub fn get(&self, index: usize) -> Option<&BoxDynNode<'a>> {
+		self.nodes.get(index)
+	}
+	// for each
+	pub fn for_each<F>(&mut self, mut handle: F) -> &mut Self
+	where
+		F: FnMut(usize, &mut BoxDynNode) -> bool,
+	{
+		for (index, node) in self.get_mut_ref().iter_mut().enumerate() {
+			if !handle(index, node) {
+				break;
+			}
+		}
+		self
+	}
+
+	// alias for `for_each`
+	pub fn each<F>(&mut self, handle: F) -> &mut Self
+	where
+		F: FnMut(usize, &mut BoxDynNode) -> bool,
+	{
+		self.for_each(handle)
+	}
+
+	// filter_by
+	pub fn filter_by<F>(&self, handle: F) -> Nodes<'a>
+	where
+		F: Fn(usize, &BoxDynNode) -> bool,
+	{
+		let mut result: Nodes = Nodes::with_capacity(self.length());
+		for (index, node) in self.get_ref().iter().enumerate() {
+			if handle(index, node) {
+				result.get_mut_ref().push(node.clone_node());
+			}
+		}
+		result
+	}
+	// filter by the node type, e.g. `INodeType::Comment`
+	pub fn filter_type(&self, node_type: INodeType) -> Nodes<'a> {
+		self.filter_by(|_, node| node.node_type() == node_type)
+	}
+	// the element nodes
+	pub fn elements(&self) -> Elements<'a> {
+		let mut result = Elements::with_capacity(self.length());
+		for node in self.get_ref() {
+			if node.node_type().is_element() {
+				if let Some(ele) = node.clone_node().typed().into_element() {
+					result.push(ele);
+				}
+			}
+		}
+		result
+	}
+	cfg_feat_text! {
+		// the text nodes
+		pub fn texts(&self) -> Texts<'a> {
+			let mut result = Texts::with_capacity(self.length());
+			for node in self.get_ref() {
+				if let Some(text) = node.clone_node().typed().into_text() {
+					result.get_mut_ref().push(text);
+				}
+			}
+			result
+		}
+	}
+}
+
+impl<'a> IntoIterator for Nodes<'a> {
+	type Item = BoxDynNode<'a>;
+	type IntoIter = Box<dyn Iterator<Item = Self::Item> + 'a>;
+	fn into_iter(self) -> Self::IntoIter {
+		Box::new(self.nodes.into_iter())
+	}
+}
@@ -1,4 +1,3 @@
-
 use super::BoxDynText;
 
 pub struct Texts<'a> {
@@ -1,4 +1,45 @@
+cfg_feat_mutation! {
+	use crate::mesdoc::error::Error as IError;
+}
 use super::INodeTrait;
 
 pub type BoxDynUncareNode<'a> = Box<dyn IUncareNodeTrait + 'a>;
-pub trait IUncareNodeTrait: INodeTrait {}
+/// The comment, CDATA and doctype nodes.
+pub trait IUncareNodeTrait: INodeTrait {
+	// the content of the comment or CDATA node, the content after `<!DOCTYPE` of the doctype node
+	fn data(&self) -> Option<String> {
+		None
+	}
+	cfg_feat_mutation! {
+		// set the content of the comment or CDATA node
+		fn set_data(&mut self, _data: &str) {
+			if let Some(doc) = &self.owner_document() {
+				doc.trigger_error(Box::new(IError::InvalidTraitMethodCall {
+					method: "set_data".into(),
+					message: "The set_data method is not implemented.".into(),
+				}));
+			}
+		}
+		// remove the node
+		fn remove(self: Box<Self>) {
+			if let Some(doc) = &self.owner_document() {
+				doc.trigger_error(Box::new(IError::InvalidTraitMethodCall {
+					method: "remove".into(),
+					message: "The remove method is not implemented.".into(),
+				}));
+			}
+		}
+	}
+	// the name of the doctype node, e.g. `html`, `None` for the other nodes
+	fn name(&self) -> Option<String> {
+		None
+	}
+	// the public identifier of the doctype node
+	fn public_id(&self) -> Option<String> {
+		None
+	}
+	// the system identifier of the doctype node
+	fn system_id(&self) -> Option<String> {
+		None
+	}
+}
@@ -1,15 +1,34 @@
+use super::namespace::{get_declared_default_namespace, get_element_namespace};
 use crate::mesdoc::constants::{NAME_SELECTOR_ALL, PRIORITY_ALL_SELECTOR};
 use crate::mesdoc::interface::Elements;
 use crate::mesdoc::selector::rule::{Matcher, RuleDefItem, RuleItem};
+use crate::mesdoc::selector::MatchedQueue;
 /// selector: `*`
 pub fn init(rules: &mut Vec<RuleItem>) {
 	let rule: RuleItem = RuleDefItem(
 		NAME_SELECTOR_ALL,
 		"*",
 		PRIORITY_ALL_SELECTOR,
-		Box::new(|_| Matcher {
-			all_handle: Some(Box::new(|eles: &Elements, _| eles.cloned())),
-			..Default::default()
+		Box::new(|data: MatchedQueue| {
+			// the default namespace declared by `Vis::with_namespaces` applies to the `*` in the selector,
+			// the `*` made for the combinators has no matched data, it always matches all the elements
+			let namespace = if data.is_empty() {
+				None
+			} else {
+				get_declared_default_namespace()
+			};
+			match namespace {
+				Some(uri) => Matcher {
+					one_handle: Some(Box::new(move |ele, _| {
+						get_element_namespace(ele).as_deref() == Some(uri.as_str())
+					})),
+					..Default::default()
+				},
+				None => Matcher {
+					all_handle: Some(Box::new(|eles: &Elements, _| eles.cloned())),
+					..Default::default()
+				},
+			}
 		}),
 	)
 	.into();
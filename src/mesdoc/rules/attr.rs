@@ -1,87 +1,200 @@
 #![allow(clippy::or_fun_call)]
 
-use crate::mesdoc::constants::{NAME_SELECTOR_ATTR, PRIORITY_ATTR_SELECTOR};
+use super::namespace::{resolve_prefix, NamespaceMatch};
+use crate::mesdoc::constants::{NAME_SELECTOR_ATTR, NAME_SELECTOR_NS_ATTR, PRIORITY_ATTR_SELECTOR};
 use crate::mesdoc::interface::IAttrValue;
+use crate::mesdoc::selector::pattern::RegExp;
 use crate::mesdoc::selector::rule::Matcher;
 use crate::mesdoc::selector::rule::{RuleDefItem, RuleItem};
 use crate::mesdoc::selector::MatchedQueue;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+// lowercase the ascii characters of the value if the `i` flag is setted
+fn normalize(v: &str, ignore_case: bool) -> Cow<'_, str> {
+	if ignore_case {
+		Cow::Owned(v.to_ascii_lowercase())
+	} else {
+		Cow::Borrowed(v)
+	}
+}
+
+// the value part of the attribute selector, e.g. `="value" i`, `^=value`, `~/regex/`
+macro_rules! attr_value_context {
+	() => {
+		r##"{regexp#(?:([*^$~|!]?)=\s*(?:'((?:\\?+.)*?)'|([^\s\]'"<>/=`]+)|"((?:\\?+.)*?)")(?:\s*([iIsS]))?|~\s*/((?:\\.|[^\\/])+)/([a-zA-Z]*))?#}"##
+	};
+}
+
+type ValueHandle = Box<dyn Fn(&Option<IAttrValue>) -> bool>;
+
+// make the handle that checks the attribute value by the matched value data
+fn make_value_handle(value_data: &HashMap<String, String>) -> ValueHandle {
+	let def_mode = String::from("");
+	if let Some(source) = value_data.get("6") {
+		// match the value with a regex, e.g. `[href~/\d+/]`
+		let flags = value_data.get("7").map(|s| s.as_str()).unwrap_or("");
+		let regex = RegExp::from_literal(source, flags).expect("The regex has been checked");
+		return Box::new(move |val: &Option<IAttrValue>| match val {
+			Some(IAttrValue::Value(v, _)) => regex.is_match(v),
+			Some(IAttrValue::True) => regex.is_match(""),
+			None => false,
+		});
+	}
+	let attr_value = value_data
+		.get("2")
+		.or_else(|| value_data.get("3"))
+		.or_else(|| value_data.get("4"))
+		.map(|s| s.clone());
+	let match_mode = value_data.get("1").unwrap_or(&def_mode);
+	// the `i` flag compares the value ascii case-insensitively, the `s` flag keeps case-sensitive
+	let ignore_case = value_data
+		.get("5")
+		.map(|flag| flag.eq_ignore_ascii_case("i"))
+		.unwrap_or(false);
+	let attr_value = attr_value.map(|v| normalize(&v, ignore_case).into_owned());
+	if let Some(attr_value) = attr_value {
+		let match_mode = match_mode.as_str();
+		if attr_value.is_empty() && !matches!(match_mode, "" | "!" | "|") {
+			// empty attribute value, ^$*
+			Box::new(|_val: &Option<IAttrValue>| false)
+		} else {
+			match match_mode {
+				// begin with value
+				"^" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => normalize(v, ignore_case).starts_with(&attr_value),
+					_ => false,
+				}),
+				// end with value
+				"$" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => normalize(v, ignore_case).ends_with(&attr_value),
+					_ => false,
+				}),
+				// contains value
+				"*" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => normalize(v, ignore_case).contains(&attr_value),
+					_ => false,
+				}),
+				// either equal to value or start with `value` and followed `-`
+				"|" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => {
+						let v = normalize(v, ignore_case);
+						if v == attr_value {
+							return true;
+						}
+						let attr_value: String = format!("{}-", attr_value);
+						v.starts_with(&attr_value)
+					}
+					_ => attr_value.is_empty(),
+				}),
+				// in a value list that splitted by whitespaces
+				"~" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => {
+						let v = normalize(v, ignore_case);
+						let split_v = v.split_ascii_whitespace();
+						for v in split_v {
+							if v == attr_value {
+								return true;
+							}
+						}
+						false
+					}
+					_ => false,
+				}),
+				// has a attribute and who's value not equal to setted value
+				"!" => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => normalize(v, ignore_case) != attr_value,
+					_ => !attr_value.is_empty(),
+				}),
+				// equal to value
+				_ => Box::new(move |val: &Option<IAttrValue>| match val {
+					Some(IAttrValue::Value(v, _)) => normalize(v, ignore_case) == attr_value,
+					_ => attr_value.is_empty(),
+				}),
+			}
+		}
+	} else {
+		// has the attribute name
+		Box::new(|val: &Option<IAttrValue>| val.is_some())
+	}
+}
+
+// check the regex of the value data
+fn check_value_data(value_data: &HashMap<String, String>) -> Result<(), String> {
+	if let Some(source) = value_data.get("6") {
+		let flags = value_data.get("7").map(|s| s.as_str()).unwrap_or("");
+		RegExp::from_literal(source, flags)?;
+	}
+	Ok(())
+}
+
 pub fn init(rules: &mut Vec<RuleItem>) {
+	// `[ns|attr]`, `[*|attr]`, `[|attr]`, must be tried before the attribute selector
 	let rule = RuleDefItem(
-		NAME_SELECTOR_ATTR,
-		r##"[{spaces}{attr_key}{spaces}{regexp#(?:([*^$~|!]?)=\s*(?:'((?:\\?+.)*?)'|([^\s\]'"<>/=`]+)|"((?:\\?+.)*?)"))?#}{spaces}]"##,
+		NAME_SELECTOR_NS_ATTR,
+		concat!(
+			r##"[{spaces}{regexp#(?:([a-zA-Z_][\w-]*)|(\*))?\|#}{attr_key}{spaces}"##,
+			attr_value_context!(),
+			"{spaces}]"
+		),
 		PRIORITY_ATTR_SELECTOR,
 		Box::new(|data: MatchedQueue| {
-			let def_mode = String::from("");
-			let attr_key = data[2].chars.iter().collect::<String>();
-			let value_data = &data[4].data;
-			let attr_value = value_data
-				.get("2")
-				.or_else(|| value_data.get("3"))
-				.or_else(|| value_data.get("4"))
-				.map(|s| s.clone());
-			let match_mode = value_data.get("1").unwrap_or(&def_mode);
-			let handle: Box<dyn Fn(&Option<IAttrValue>) -> bool> = if let Some(attr_value) = attr_value {
-				let match_mode = match_mode.as_str();
-				if attr_value.is_empty() && !matches!(match_mode, "" | "!" | "|") {
-					// empty attribute value, ^$*
-					Box::new(|_val: &Option<IAttrValue>| false)
-				} else {
-					match match_mode {
-						// begin with value
-						"^" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => v.starts_with(&attr_value),
-							_ => false,
-						}),
-						// end with value
-						"$" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => v.ends_with(&attr_value),
-							_ => false,
-						}),
-						// contains value
-						"*" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => v.contains(&attr_value),
-							_ => false,
-						}),
-						// either equal to value or start with `value` and followed `-`
-						"|" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => {
-								if *v == attr_value {
-									return true;
-								}
-								let attr_value: String = format!("{}-", attr_value);
-								v.starts_with(&attr_value)
+			let namespace = NamespaceMatch::from_data(&data[2].data, "1", "2");
+			let local_name = data[3]
+				.chars
+				.iter()
+				.collect::<String>()
+				.to_ascii_lowercase();
+			let handle = make_value_handle(&data[5].data);
+			Matcher {
+				one_handle: Some(Box::new(move |ele, _| {
+					let values = ele
+						.get_attributes()
+						.into_iter()
+						.filter_map(|(name, value)| {
+							let (prefix, cur_local_name) = match name.split_once(':') {
+								Some((prefix, local)) if !prefix.is_empty() => (Some(prefix), local),
+								_ => (None, name.as_str()),
+							};
+							if cur_local_name != local_name {
+								return None;
 							}
-							_ => attr_value.is_empty(),
-						}),
-						// in a value list that splitted by whitespaces
-						"~" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => {
-								let split_v = v.split_ascii_whitespace();
-								for v in split_v {
-									if v == attr_value {
-										return true;
-									}
-								}
-								false
+							// the unprefixed attribute has no namespace
+							let cur_namespace = prefix.and_then(|prefix| resolve_prefix(ele, prefix));
+							if namespace.is_match(cur_namespace.as_deref()) {
+								Some(value)
+							} else {
+								None
 							}
-							_ => false,
-						}),
-						// has a attribute and who's value not equal to setted value
-						"!" => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => attr_value != *v,
-							_ => !attr_value.is_empty(),
-						}),
-						// equal to value
-						_ => Box::new(move |val: &Option<IAttrValue>| match val {
-							Some(IAttrValue::Value(v, _)) => *v == attr_value,
-							_ => attr_value.is_empty(),
-						}),
+						})
+						.collect::<Vec<IAttrValue>>();
+					if values.is_empty() {
+						return handle(&None);
 					}
-				}
-			} else {
-				// has the attribute name
-				Box::new(|val: &Option<IAttrValue>| val.is_some())
-			};
+					values.into_iter().any(|value| handle(&Some(value)))
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(|data: &MatchedQueue| {
+		NamespaceMatch::check(&data[2].data, "1")?;
+		check_value_data(&data[5].data)
+	}));
+	rules.push(rule);
+	// `[attr]`, `[attr=value]`
+	let rule = RuleDefItem(
+		NAME_SELECTOR_ATTR,
+		concat!(
+			"[{spaces}{attr_key}{spaces}",
+			attr_value_context!(),
+			"{spaces}]"
+		),
+		PRIORITY_ATTR_SELECTOR,
+		Box::new(|data: MatchedQueue| {
+			let attr_key = data[2].chars.iter().collect::<String>();
+			let handle = make_value_handle(&data[4].data);
 			Matcher {
 				one_handle: Some(Box::new(move |ele, _| {
 					let val = ele.get_attribute(&attr_key);
@@ -91,5 +204,9 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 			}
 		}),
 	);
-	rules.push(rule.into());
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(|data: &MatchedQueue| {
+		check_value_data(&data[4].data)
+	}));
+	rules.push(rule);
 }
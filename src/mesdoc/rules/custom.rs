@@ -0,0 +1,112 @@
+use crate::mesdoc::constants::PRIORITY_PSEUDO_SELECTOR;
+use crate::mesdoc::error::Error;
+use crate::mesdoc::interface::{Elements, IElementTrait};
+use crate::mesdoc::selector::rule::{has_rule, insert_rule, Matcher, Rule, RuleDefItem};
+use crate::mesdoc::selector::MatchedQueue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A parameter matched by the pattern of a custom pseudo selector.
+#[derive(Debug, Clone)]
+pub struct PseudoParam {
+	/// The pattern name, e.g. `selector`, `nth`, `regexp`, `identity`.
+	pub name: &'static str,
+	/// The matched content.
+	pub value: String,
+	/// The captured data, e.g. the groups of `regexp`, the `n` and `index` of `nth`.
+	pub data: HashMap<String, String>,
+}
+
+pub type PseudoOneHandle = dyn Fn(&dyn IElementTrait, &[PseudoParam]) -> bool + Send + Sync;
+pub type PseudoAllHandle =
+	dyn for<'r> Fn(&Elements<'r>, &[PseudoParam]) -> Elements<'r> + Send + Sync;
+
+pub(crate) enum PseudoHandle {
+	One(Arc<PseudoOneHandle>),
+	All(Arc<PseudoAllHandle>),
+}
+
+// keep the patterns' matched data, ignore the raw characters and spaces
+fn to_params(data: MatchedQueue) -> Vec<PseudoParam> {
+	data
+		.into_iter()
+		.filter(|matched| !matches!(matched.name, "" | "spaces"))
+		.map(|matched| PseudoParam {
+			name: matched.name,
+			value: matched.chars.into_iter().collect(),
+			data: matched.data,
+		})
+		.collect()
+}
+
+// get the pseudo name from the selector context, e.g. ':price-like' of ':price-like({spaces}{nth}{spaces})'
+fn get_pseudo_name(selector: &str) -> Option<&str> {
+	let name = selector.strip_prefix(':')?;
+	let name_len = name
+		.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))
+		.unwrap_or(name.len());
+	if name_len == 0 {
+		return None;
+	}
+	Some(&selector[..name_len + 1])
+}
+
+/// add a custom pseudo selector
+pub(crate) fn add_pseudo(selector: &str, handle: PseudoHandle) -> Result<(), Error> {
+	let name = get_pseudo_name(selector).ok_or_else(|| Error::InvalidSelector {
+		context: String::from(selector),
+		reason: String::from("A custom pseudo selector must start with ':' followed by a name"),
+	})?;
+	// check the rule before leak the strings, so a failed registration doesn't leak
+	let invalid = |reason: String| Error::InvalidSelector {
+		context: String::from(selector),
+		reason,
+	};
+	if has_rule(name) {
+		return Err(invalid(format!("The rule '{}' is already exist.", name)));
+	}
+	Rule::parse_queues(selector).map_err(invalid)?;
+	// the rules are registered once and live as long as the program
+	let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+	let context: &'static str = Box::leak(selector.to_string().into_boxed_str());
+	let rule = RuleDefItem(
+		name,
+		context,
+		PRIORITY_PSEUDO_SELECTOR,
+		Box::new(move |data: MatchedQueue| {
+			let params = to_params(data);
+			match &handle {
+				PseudoHandle::One(handle) => {
+					let handle = Arc::clone(handle);
+					Matcher {
+						one_handle: Some(Box::new(move |ele, _| handle(ele, &params))),
+						..Default::default()
+					}
+				}
+				PseudoHandle::All(handle) => {
+					let handle = Arc::clone(handle);
+					Matcher {
+						all_handle: Some(Box::new(move |eles: &Elements, _| handle(eles, &params))),
+						..Default::default()
+					}
+				}
+			}
+		}),
+	);
+	insert_rule(rule.into()).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::get_pseudo_name;
+	#[test]
+	fn test_get_pseudo_name() {
+		assert_eq!(get_pseudo_name(":price"), Some(":price"));
+		assert_eq!(
+			get_pseudo_name(":price-like({spaces}{nth}{spaces})"),
+			Some(":price-like")
+		);
+		assert_eq!(get_pseudo_name("price"), None);
+		assert_eq!(get_pseudo_name(":(a)"), None);
+	}
+}
@@ -45,6 +45,7 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 					..Default::default()
 				}
 			}),
+			checker: None,
 			queues: Vec::new(),
 		},
 	};
@@ -2,8 +2,10 @@ use crate::mesdoc::selector::rule::{self, add_rules, RuleItem};
 pub(crate) mod all;
 pub(crate) mod attr;
 pub(crate) mod class;
+pub(crate) mod custom;
 pub(crate) mod id;
 pub(crate) mod name;
+pub(crate) mod namespace;
 pub(crate) mod pseudo;
 pub(crate) fn init() {
 	// init rule
@@ -13,6 +15,7 @@ pub(crate) fn init() {
 	// keep the init order
 	class::init(&mut rules);
 	id::init(&mut rules);
+	namespace::init(&mut rules);
 	name::init(&mut rules);
 	attr::init(&mut rules);
 	pseudo::init(&mut rules);
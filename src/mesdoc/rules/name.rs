@@ -1,3 +1,4 @@
+use super::namespace::{get_declared_default_namespace, get_element_namespace};
 use crate::mesdoc::constants::{NAME_SELECTOR_NAME, PRIORITY_NAME_SELECTOR};
 use crate::mesdoc::selector::rule::{Matcher, RuleDefItem, RuleItem};
 use crate::mesdoc::selector::MatchedQueue;
@@ -10,9 +11,14 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 		PRIORITY_NAME_SELECTOR,
 		Box::new(|mut data: MatchedQueue| {
 			let name = data.remove(0).chars;
+			// the default namespace declared by `Vis::with_namespaces` applies to the type selectors
+			let namespace = get_declared_default_namespace();
 			Matcher {
 				one_handle: Some(Box::new(move |ele, _| {
 					is_equal_chars_ignore_case(&ele.tag_names(), &name)
+						&& namespace
+							.as_ref()
+							.map_or(true, |uri| get_element_namespace(ele).as_ref() == Some(uri))
 				})),
 				..Default::default()
 			}
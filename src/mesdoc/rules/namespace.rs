@@ -0,0 +1,214 @@
+use crate::mesdoc::constants::{
+	BUILTIN_NAMESPACES, NAMESPACE_HTML, NAMESPACE_MATHML, NAMESPACE_SVG, NAME_SELECTOR_NS_NAME,
+	PRIORITY_NAME_SELECTOR,
+};
+use crate::mesdoc::interface::{BoxDynElement, IAttrValue, IElementTrait};
+use crate::mesdoc::selector::rule::{Matcher, RuleDefItem, RuleItem};
+use crate::mesdoc::selector::MatchedQueue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+	// the namespace prefixes declared by `Vis::with_namespaces`, the empty prefix is the default namespace
+	static DECLARED_NAMESPACES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+// restore the outer declarations even if the handle panics
+struct DeclaredGuard(Option<HashMap<String, String>>);
+
+impl Drop for DeclaredGuard {
+	fn drop(&mut self) {
+		let outer = self.0.take().unwrap_or_default();
+		DECLARED_NAMESPACES.with(|declared| *declared.borrow_mut() = outer);
+	}
+}
+
+/// declare the namespace prefixes for the selectors parsed in the handle, the outer declarations are inherited
+pub(crate) fn with_namespaces<F, R>(namespaces: &[(&str, &str)], handle: F) -> R
+where
+	F: FnOnce() -> R,
+{
+	let outer = DECLARED_NAMESPACES.with(|declared| {
+		let mut declared = declared.borrow_mut();
+		let outer = declared.clone();
+		for (prefix, uri) in namespaces {
+			declared.insert(prefix.to_ascii_lowercase(), String::from(*uri));
+		}
+		outer
+	});
+	let _guard = DeclaredGuard(Some(outer));
+	handle()
+}
+
+/// the declared namespaces in current thread
+pub(crate) fn get_declared_namespaces() -> Vec<(String, String)> {
+	DECLARED_NAMESPACES.with(|declared| {
+		declared
+			.borrow()
+			.iter()
+			.map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+			.collect()
+	})
+}
+
+/// the namespace of a prefix declared by `Vis::with_namespaces`, or a built-in prefix
+pub(crate) fn get_declared_namespace(prefix: &str) -> Option<String> {
+	let prefix = prefix.to_ascii_lowercase();
+	DECLARED_NAMESPACES
+		.with(|declared| declared.borrow().get(&prefix).cloned())
+		.or_else(|| get_builtin_namespace(&prefix).map(String::from))
+}
+
+/// the default namespace declared by the empty prefix
+pub(crate) fn get_declared_default_namespace() -> Option<String> {
+	DECLARED_NAMESPACES.with(|declared| declared.borrow().get("").cloned())
+}
+
+fn get_builtin_namespace(prefix: &str) -> Option<&'static str> {
+	BUILTIN_NAMESPACES
+		.iter()
+		.find(|(name, _)| *name == prefix)
+		.map(|(_, uri)| *uri)
+}
+
+// split the `prefix:local` name, the prefix is None if the name has no colon
+fn split_qualified_name(name: &str) -> (Option<&str>, &str) {
+	match name.split_once(':') {
+		Some((prefix, local)) if !prefix.is_empty() && !local.is_empty() => (Some(prefix), local),
+		_ => (None, name),
+	}
+}
+
+fn get_parent_element<'b>(ele: &dyn IElementTrait) -> Option<BoxDynElement<'b>> {
+	ele.parent().filter(|parent| !parent.is_root_element())
+}
+
+fn get_local_name(ele: &dyn IElementTrait) -> String {
+	let tag_name: String = ele.tag_names().into_iter().collect();
+	String::from(split_qualified_name(&tag_name).1)
+}
+
+/// resolve the prefix by the nearest `xmlns:prefix` attribute, fallback to the built-in prefixes
+pub(crate) fn resolve_prefix(ele: &dyn IElementTrait, prefix: &str) -> Option<String> {
+	let attr_name = format!("xmlns:{}", prefix);
+	let mut cur = ele.get_attribute(&attr_name);
+	let mut parent = get_parent_element(ele);
+	while cur.is_none() {
+		match parent {
+			Some(ele) => {
+				cur = ele.get_attribute(&attr_name);
+				parent = get_parent_element(&*ele);
+			}
+			None => break,
+		}
+	}
+	match cur {
+		Some(IAttrValue::Value(uri, _)) if !uri.is_empty() => Some(uri),
+		Some(_) => None,
+		None => get_builtin_namespace(&prefix.to_ascii_lowercase()).map(String::from),
+	}
+}
+
+// the default namespace of the unprefixed elements: the nearest `xmlns` attribute,
+// or the `svg` and `math` foreign elements in html, the children of `foreignObject` are back to html
+fn get_default_namespace(ele: &dyn IElementTrait) -> Option<String> {
+	if let Some(value) = ele.get_attribute("xmlns") {
+		return match value {
+			IAttrValue::Value(uri, _) if !uri.is_empty() => Some(uri),
+			_ => None,
+		};
+	}
+	match get_local_name(ele).as_str() {
+		"svg" => return Some(String::from(NAMESPACE_SVG)),
+		"math" => return Some(String::from(NAMESPACE_MATHML)),
+		_ => {}
+	}
+	match get_parent_element(ele) {
+		Some(parent) if get_local_name(&*parent) == "foreignobject" => {
+			Some(String::from(NAMESPACE_HTML))
+		}
+		Some(parent) => get_default_namespace(&*parent),
+		None => Some(String::from(NAMESPACE_HTML)),
+	}
+}
+
+/// the namespace of the element, None means the element has no namespace, e.g. `xmlns=""`
+pub(crate) fn get_element_namespace(ele: &dyn IElementTrait) -> Option<String> {
+	let tag_name: String = ele.tag_names().into_iter().collect();
+	match split_qualified_name(&tag_name) {
+		(Some(prefix), _) => resolve_prefix(ele, prefix),
+		(None, _) => get_default_namespace(ele),
+	}
+}
+
+/// the namespace in a selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NamespaceMatch {
+	// `*|`
+	Any,
+	// `|`
+	Empty,
+	// `prefix|`
+	Uri(String),
+}
+
+impl NamespaceMatch {
+	/// get the namespace from the prefix group and the star group of the matched regexp
+	pub(crate) fn from_data(data: &HashMap<String, String>, prefix: &str, star: &str) -> Self {
+		if data.contains_key(star) {
+			return NamespaceMatch::Any;
+		}
+		match data.get(prefix) {
+			Some(prefix) => NamespaceMatch::Uri(
+				get_declared_namespace(prefix).expect("The namespace prefix has been checked"),
+			),
+			None => NamespaceMatch::Empty,
+		}
+	}
+	/// check if the prefix is declared
+	pub(crate) fn check(data: &HashMap<String, String>, prefix: &str) -> Result<(), String> {
+		match data.get(prefix) {
+			Some(prefix) if get_declared_namespace(prefix).is_none() => {
+				Err(format!("The namespace prefix '{}' is not declared", prefix))
+			}
+			_ => Ok(()),
+		}
+	}
+	pub(crate) fn is_match(&self, namespace: Option<&str>) -> bool {
+		match self {
+			NamespaceMatch::Any => true,
+			NamespaceMatch::Empty => namespace.is_none(),
+			NamespaceMatch::Uri(uri) => namespace == Some(uri.as_str()),
+		}
+	}
+}
+
+/// selector: `ns|E`, `*|E`, `|E`, `ns|*`
+pub fn init(rules: &mut Vec<RuleItem>) {
+	let rule = RuleDefItem(
+		NAME_SELECTOR_NS_NAME,
+		r##"{regexp#(?:([a-zA-Z_][\w-]*)|(\*))?\|(?:([a-zA-Z_][\w-]*)|(\*))#}"##,
+		PRIORITY_NAME_SELECTOR,
+		Box::new(|data: MatchedQueue| {
+			let data = &data[0].data;
+			let namespace = NamespaceMatch::from_data(data, "1", "2");
+			let local_name = data.get("3").cloned();
+			Matcher {
+				one_handle: Some(Box::new(move |ele, _| {
+					if let Some(local_name) = &local_name {
+						if !get_local_name(ele).eq_ignore_ascii_case(local_name) {
+							return false;
+						}
+					}
+					namespace.is_match(get_element_namespace(ele).as_deref())
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(|data: &MatchedQueue| {
+		NamespaceMatch::check(&data[0].data, "1")
+	}));
+	rules.push(rule);
+}
@@ -1,20 +1,41 @@
 #![deny(clippy::print_stdout)]
 use crate::mesdoc::interface::{BoxDynElement, Elements, IAttrValue, IElementTrait, INodeType};
-use crate::mesdoc::selector::pattern::Nth;
+use crate::mesdoc::selector::pattern::{Nth, RegExp};
 use crate::mesdoc::selector::rule::{Matcher, Rule, RuleDefItem, RuleItem};
-use crate::mesdoc::selector::MatchedQueue;
+use crate::mesdoc::selector::{MatchedQueue, Selector};
 use crate::mesdoc::utils::{contains_chars, is_equal_chars};
 use crate::mesdoc::{
 	constants::{
-		DEF_NODES_LEN, PRIORITY_PSEUDO_SELECTOR, SELECTOR_ALIAS_NAME_HEADER, SELECTOR_ALIAS_NAME_INPUT,
-		SELECTOR_ALIAS_NAME_SUBMIT,
+		DEF_NODES_LEN, PRIORITY_PSEUDO_SELECTOR, SELECTOR_ALIAS_NAME_BUTTON,
+		SELECTOR_ALIAS_NAME_CHECKBOX, SELECTOR_ALIAS_NAME_FILE, SELECTOR_ALIAS_NAME_HEADER,
+		SELECTOR_ALIAS_NAME_IMAGE, SELECTOR_ALIAS_NAME_INPUT, SELECTOR_ALIAS_NAME_PASSWORD,
+		SELECTOR_ALIAS_NAME_RADIO, SELECTOR_ALIAS_NAME_RESET, SELECTOR_ALIAS_NAME_SUBMIT,
+		SELECTOR_ALIAS_NAME_TEXT,
 	},
 	selector::rule::MatchSpecifiedHandle,
 };
 use std::{cmp::Ordering, collections::VecDeque};
-use std::{collections::HashMap, ops::Range};
+use std::{
+	collections::{HashMap, HashSet},
+	ops::Range,
+};
 const PRIORITY: u32 = PRIORITY_PSEUDO_SELECTOR;
 
+// parse the nested selector list, e.g. `.a, .b` of `:is(.a, .b)`
+fn parse_nested(data: &MatchedQueue, index: usize) -> Result<Selector, String> {
+	let context = data[index].chars.iter().collect::<String>();
+	Selector::from_str(&context, false).map_err(|err| err.to_string())
+}
+
+// the nested selector list is parsed once when the matcher is made, so check it before
+fn with_nested_checker(rule: RuleDefItem, index: usize) -> RuleItem {
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(move |data: &MatchedQueue| {
+		parse_nested(data, index).map(|_| ())
+	}));
+	rule
+}
+
 fn nth_index_to_number(index: &Option<String>) -> isize {
 	index
 		.as_ref()
@@ -65,32 +86,50 @@ fn pseudo_root(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+// check if the element has no child nodes except comments
+fn is_empty_element(ele: &dyn IElementTrait) -> bool {
+	ele
+		.child_nodes()
+		.iter()
+		.all(|node| matches!(node.node_type(), INodeType::Comment))
+}
+
 /// pseudo selector ":empty"
 fn pseudo_empty(rules: &mut Vec<RuleItem>) {
 	// empty
 	let selector = ":empty";
 	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| is_empty_element(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector ":blank"
+fn pseudo_blank(rules: &mut Vec<RuleItem>) {
+	// empty, or only contains whitespaces and comments
+	let selector = ":blank";
+	let name = selector;
 	let rule = RuleDefItem(
 		name,
 		selector,
 		PRIORITY,
 		Box::new(|_| Matcher {
 			one_handle: Some(Box::new(|ele, _| {
-				let child_nodes = ele.child_nodes();
-				if child_nodes.is_empty() {
-					return true;
-				}
-				let mut only_comments = true;
-				for node in child_nodes {
-					match node.node_type() {
-						INodeType::Comment => continue,
-						_ => {
-							only_comments = false;
-							break;
-						}
-					}
-				}
-				only_comments
+				ele.child_nodes().iter().all(|node| match node.node_type() {
+					INodeType::Comment => true,
+					INodeType::Text => node
+						.text_contents()
+						.iter()
+						.all(|ch| ch.is_ascii_whitespace()),
+					_ => false,
+				})
 			})),
 			..Default::default()
 		}),
@@ -389,6 +428,95 @@ fn pseudo_nth_last_child(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+// make for 'nth-child', 'nth-last-child' with `of <selector-list>`, only count the siblings matched the selector list
+fn make_asc_or_desc_nth_child_of(selector: &'static str, asc: bool) -> RuleItem {
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(move |data: MatchedQueue| {
+			let nth_data = &data[2].data;
+			let n = nth_data.get("n").cloned();
+			let index = nth_data.get("index").cloned();
+			let selector = parse_nested(&data, 4).expect("The nested selector has been checked");
+			Matcher {
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					let mut result: Elements = Elements::with_capacity(DEF_NODES_LEN);
+					// the positions of the matched siblings and the allowed indexs, cached by the parent's node id
+					let mut siblings_cache: HashMap<usize, (HashMap<usize, usize>, Vec<usize>)> =
+						HashMap::with_capacity(DEF_NODES_LEN);
+					for ele in eles.get_ref() {
+						let parent = match ele.parent() {
+							Some(parent) => parent,
+							None => continue,
+						};
+						// the nodes without id can't be cached, find the position in the siblings
+						let (Some(parent_id), Some(ele_id)) = (parent.node_id(), ele.node_id()) else {
+							let siblings = parent.children().filter_selector(&selector);
+							let total = siblings.length();
+							if let Some(position) = siblings
+								.get_ref()
+								.iter()
+								.position(|sibling| sibling.is(ele))
+							{
+								let position = if asc { position } else { total - position - 1 };
+								if Nth::get_allowed_indexs(&n, &index, total)
+									.binary_search(&position)
+									.is_ok()
+								{
+									result.push(ele.cloned());
+								}
+							}
+							continue;
+						};
+						let (positions, allow_indexs) = siblings_cache.entry(parent_id).or_insert_with(|| {
+							let siblings = parent.children().filter_selector(&selector);
+							let total = siblings.length();
+							let positions = siblings
+								.get_ref()
+								.iter()
+								.enumerate()
+								.filter_map(|(position, sibling)| {
+									let position = if asc { position } else { total - position - 1 };
+									sibling.node_id().map(|node_id| (node_id, position))
+								})
+								.collect::<HashMap<usize, usize>>();
+							(positions, Nth::get_allowed_indexs(&n, &index, total))
+						});
+						if let Some(position) = positions.get(&ele_id) {
+							if allow_indexs.binary_search(position).is_ok() {
+								result.push(ele.cloned());
+							}
+						}
+					}
+					result
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	with_nested_checker(rule, 4)
+}
+
+/// pseudo selector: `:nth-child` with `of <selector-list>`
+fn pseudo_nth_child_of(rules: &mut Vec<RuleItem>) {
+	let rule = make_asc_or_desc_nth_child_of(
+		r##":nth-child({spaces}{nth}{regexp#\s+of\s+#}{selector}{spaces})"##,
+		true,
+	);
+	rules.push(rule);
+}
+
+/// pseudo selector: `:nth-last-child` with `of <selector-list>`
+fn pseudo_nth_last_child_of(rules: &mut Vec<RuleItem>) {
+	let rule = make_asc_or_desc_nth_child_of(
+		r##":nth-last-child({spaces}{nth}{regexp#\s+of\s+#}{selector}{spaces})"##,
+		false,
+	);
+	rules.push(rule);
+}
+
 // make first or last child rule
 fn make_first_or_last_child(selector: &'static str, asc: bool) -> RuleDefItem {
 	let name = selector;
@@ -841,25 +969,68 @@ fn pseudo_not(rules: &mut Vec<RuleItem>) {
 		selector,
 		PRIORITY,
 		Box::new(|data: MatchedQueue| {
-			let selector = data[2].chars.iter().collect::<String>();
+			let selector = parse_nested(&data, 2).expect("The nested selector has been checked");
 			Matcher {
-				all_handle: Some(Box::new(move |eles: &Elements, _| eles.not(&selector))),
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					eles.not_selector(&selector)
+				})),
 				..Default::default()
 			}
 		}),
 	);
-	rules.push(rule.into());
+	rules.push(with_nested_checker(rule, 2));
 }
 
-/// pseudo selector: `:contains`
-fn pseudo_contains(rules: &mut Vec<RuleItem>) {
-	let name = ":contains";
-	let selector = r##":contains({spaces}{regexp#(?:'((?:\\?+.)*?)'|"((?:\\?+.)*?)"|([^)\s'"<>/=`]*))#}{spaces})"##;
+// make for ':is', ':where', match any selector in the selector list
+fn make_matches_any(name: &'static str, selector: &'static str) -> RuleItem {
 	let rule = RuleDefItem(
 		name,
 		selector,
 		PRIORITY,
-		Box::new(|mut data: MatchedQueue| {
+		Box::new(|data: MatchedQueue| {
+			let selector = parse_nested(&data, 2).expect("The nested selector has been checked");
+			Matcher {
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					eles.filter_selector(&selector)
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	with_nested_checker(rule, 2)
+}
+
+/// pseudo selector: `:is`
+fn pseudo_is(rules: &mut Vec<RuleItem>) {
+	let rule = make_matches_any(":is", ":is({spaces}{selector}{spaces})");
+	rules.push(rule);
+}
+
+/// pseudo selector: `:where`
+fn pseudo_where(rules: &mut Vec<RuleItem>) {
+	// same as ':is', the difference of specificity has no effect on matching
+	let rule = make_matches_any(":where", ":where({spaces}{selector}{spaces})");
+	rules.push(rule);
+}
+
+// lowercase the characters for case-insensitive matching
+fn to_lowercase_chars(chars: &[char]) -> Vec<char> {
+	chars.iter().flat_map(|ch| ch.to_lowercase()).collect()
+}
+
+// the searched text of ':contains', ':icontains', ':contains-own', quoted or not
+const CONTAINS_PARAM: &str = r##"{regexp#(?:'((?:\\?+.)*?)'|"((?:\\?+.)*?)"|([^)\s'"<>/=`]*))#}"##;
+
+// make for ':contains', ':icontains', ':contains-own'
+fn make_contains(name: &'static str, ignore_case: bool, own: bool) -> RuleDefItem {
+	// the rules are initialized only once
+	let selector: &'static str =
+		Box::leak(format!("{}({{spaces}}{}{{spaces}})", name, CONTAINS_PARAM).into_boxed_str());
+	RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(move |mut data: MatchedQueue| {
 			let mut find_chars = data.remove(2).chars;
 			if !find_chars.is_empty() {
 				let first = find_chars[0];
@@ -869,16 +1040,29 @@ fn pseudo_contains(rules: &mut Vec<RuleItem>) {
 				} else {
 					find_chars
 				};
+				let search = if ignore_case {
+					to_lowercase_chars(&search)
+				} else {
+					search
+				};
 				Matcher {
 					one_handle: Some(Box::new(move |ele, _| {
-						let contents = ele.text_contents();
-						// let contents = vec!['b'];
-						// let contents_count = contents.len();
-						contains_chars(&contents, &search)
-						// match contents_count.cmp(&search_count) {
-						// 	Ordering::Less => false,
-						// 	_ => ,
-						// }
+						let contents = if own {
+							// only the direct text children
+							ele
+								.child_nodes()
+								.iter()
+								.filter(|node| matches!(node.node_type(), INodeType::Text))
+								.flat_map(|node| node.text_contents())
+								.collect::<Vec<char>>()
+						} else {
+							ele.text_contents()
+						};
+						if ignore_case {
+							contains_chars(&to_lowercase_chars(&contents), &search)
+						} else {
+							contains_chars(&contents, &search)
+						}
 					})),
 					..Default::default()
 				}
@@ -889,6 +1073,303 @@ fn pseudo_contains(rules: &mut Vec<RuleItem>) {
 				}
 			}
 		}),
+	)
+}
+
+/// pseudo selector: `:contains`
+fn pseudo_contains(rules: &mut Vec<RuleItem>) {
+	let rule = make_contains(":contains", false, false);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:icontains`
+fn pseudo_icontains(rules: &mut Vec<RuleItem>) {
+	let rule = make_contains(":icontains", true, false);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:contains-own`
+fn pseudo_contains_own(rules: &mut Vec<RuleItem>) {
+	let rule = make_contains(":contains-own", false, true);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:matches`
+fn pseudo_matches(rules: &mut Vec<RuleItem>) {
+	let name = ":matches";
+	let selector = r##":matches({spaces}{regexp#/((?:\\.|[^\\/])+)/([a-zA-Z]*)#}{spaces})"##;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|data: MatchedQueue| {
+			let regex_data = &data[2].data;
+			let flags = regex_data.get("2").map(|s| s.as_str()).unwrap_or("");
+			let regex =
+				RegExp::from_literal(&regex_data["1"], flags).expect("The regex has been checked");
+			Matcher {
+				one_handle: Some(Box::new(move |ele, _| regex.is_match(&ele.text()))),
+				..Default::default()
+			}
+		}),
+	);
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(|data: &MatchedQueue| {
+		let regex_data = &data[2].data;
+		let flags = regex_data.get("2").map(|s| s.as_str()).unwrap_or("");
+		RegExp::from_literal(&regex_data["1"], flags).map(|_| ())
+	}));
+	rules.push(rule);
+}
+
+// resolve the value inherited from the nearest ancestor, the resolved values are cached by node id
+fn resolve_inherited(
+	ele: &dyn IElementTrait,
+	cache: &mut HashMap<usize, String>,
+	get_own_value: fn(&dyn IElementTrait) -> Option<String>,
+	get_default_value: &mut dyn FnMut() -> String,
+) -> String {
+	let mut visited: Vec<usize> = Vec::with_capacity(DEF_NODES_LEN);
+	let mut cur = Some(ele.cloned());
+	let mut value = None;
+	while let Some(cur_ele) = cur {
+		// the nodes without id are not cached
+		let node_id = cur_ele.node_id();
+		if let Some(cached) = node_id.and_then(|node_id| cache.get(&node_id)) {
+			value = Some(cached.clone());
+			break;
+		}
+		visited.extend(node_id);
+		if let Some(own_value) = get_own_value(&*cur_ele) {
+			value = Some(own_value);
+			break;
+		}
+		cur = get_parent_element(&*cur_ele);
+	}
+	let value = value.unwrap_or_else(get_default_value);
+	for node_id in visited {
+		cache.insert(node_id, value.clone());
+	}
+	value
+}
+
+// the language of the element itself, an empty 'lang' means the language is unknown
+fn get_own_lang(ele: &dyn IElementTrait) -> Option<String> {
+	get_lower_attr(ele, "lang")
+}
+
+// the default language set by the `<meta http-equiv="content-language">`
+fn get_pragma_lang(ele: &dyn IElementTrait) -> String {
+	let mut lang = String::new();
+	if let Some(root) = ele.root_element() {
+		loop_descendants(&*root, &mut |cur| {
+			if get_tag_name(&**cur) == "meta"
+				&& get_lower_attr(&**cur, "http-equiv").as_deref() == Some("content-language")
+			{
+				if let Some(content) = get_lower_attr(&**cur, "content") {
+					// the content with multiple languages is ignored
+					if !content.contains(',') {
+						lang = content;
+					}
+				}
+				return true;
+			}
+			false
+		});
+	}
+	lang
+}
+
+// the direction of the element itself, `auto` is detected by the first strong directional character
+fn get_own_dir(ele: &dyn IElementTrait) -> Option<String> {
+	let dir = get_lower_attr(ele, "dir");
+	match dir.as_deref() {
+		Some("ltr") | Some("rtl") => dir,
+		Some("auto") => Some(detect_dir(ele)),
+		_ if get_tag_name(ele) == "bdi" => Some(detect_dir(ele)),
+		_ => None,
+	}
+}
+
+// detect the direction by the first strong directional character of the text
+fn detect_dir(ele: &dyn IElementTrait) -> String {
+	let is_rtl = ele
+		.text_contents()
+		.into_iter()
+		.find(|ch| ch.is_alphabetic())
+		.map(
+			|ch| matches!(ch, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}'),
+		)
+		.unwrap_or(false);
+	String::from(if is_rtl { "rtl" } else { "ltr" })
+}
+
+/// pseudo selector: `:lang`
+fn pseudo_lang(rules: &mut Vec<RuleItem>) {
+	let name = ":lang";
+	let selector = r##":lang({spaces}{regexp#[^()]+#}{spaces})"##;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|data: MatchedQueue| {
+			// the language ranges, e.g. `de`, `"de-DE"`, `en, fr`
+			let ranges = data[2]
+				.chars
+				.iter()
+				.collect::<String>()
+				.split(',')
+				.map(|range| {
+					range
+						.trim()
+						.trim_matches(|ch| ch == '"' || ch == '\'')
+						.to_ascii_lowercase()
+				})
+				.filter(|range| !range.is_empty())
+				.collect::<Vec<String>>();
+			Matcher {
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					let mut result = Elements::with_capacity(DEF_NODES_LEN);
+					let mut cache: HashMap<usize, String> = HashMap::with_capacity(DEF_NODES_LEN);
+					let mut pragma_lang: Option<String> = None;
+					for ele in eles.get_ref() {
+						let lang = resolve_inherited(&**ele, &mut cache, get_own_lang, &mut || {
+							pragma_lang
+								.get_or_insert_with(|| get_pragma_lang(&**ele))
+								.clone()
+						});
+						if lang.is_empty() {
+							continue;
+						}
+						// the `|=` rule, equal to the range or starts with the range followed by '-'
+						let is_matched = ranges.iter().any(|range| {
+							range == "*"
+								|| lang == *range
+								|| (lang.starts_with(range.as_str()) && lang[range.len()..].starts_with('-'))
+						});
+						if is_matched {
+							result.push(ele.cloned());
+						}
+					}
+					result
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:dir`
+fn pseudo_dir(rules: &mut Vec<RuleItem>) {
+	let name = ":dir";
+	let selector = r##":dir({spaces}{regexp#[a-zA-Z]+#}{spaces})"##;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|data: MatchedQueue| {
+			let dir = data[2]
+				.chars
+				.iter()
+				.collect::<String>()
+				.to_ascii_lowercase();
+			Matcher {
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					let mut result = Elements::with_capacity(DEF_NODES_LEN);
+					let mut cache: HashMap<usize, String> = HashMap::with_capacity(DEF_NODES_LEN);
+					for ele in eles.get_ref() {
+						let cur_dir =
+							resolve_inherited(&**ele, &mut cache, get_own_dir, &mut || String::from("ltr"));
+						if cur_dir == dir {
+							result.push(ele.cloned());
+						}
+					}
+					result
+				})),
+				..Default::default()
+			}
+		}),
+	);
+	rules.push(rule.into());
+}
+
+// check if the element is a hyperlink with 'href'
+fn is_any_link(ele: &dyn IElementTrait) -> bool {
+	matches!(get_tag_name(ele).as_str(), "a" | "area") && ele.has_attribute("href")
+}
+
+/// pseudo selector: `:any-link`, `:link`
+fn pseudo_any_link(rules: &mut Vec<RuleItem>) {
+	// a static document has no browsing history, so all the links are unvisited
+	for selector in [":any-link", ":link"] {
+		let name = selector;
+		let rule = RuleDefItem(
+			name,
+			selector,
+			PRIORITY,
+			Box::new(|_| Matcher {
+				one_handle: Some(Box::new(|ele, _| is_any_link(ele))),
+				..Default::default()
+			}),
+		);
+		rules.push(rule.into());
+	}
+}
+
+/// pseudo selector: `:target`
+fn pseudo_target(rules: &mut Vec<RuleItem>) {
+	// a static document has no url, so the fragment is the parameter, e.g. `:target(intro)`, `:target(#intro)`
+	// the '#' can't be used in the regexp pattern, use '\x23' instead
+	let name = ":target";
+	let selector = r##":target({spaces}{regexp#(?:'([^']*)'|"([^"]*)"|\x23?([^)\s'"]+))#}{spaces})"##;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|data: MatchedQueue| {
+			let target_data = &data[2].data;
+			let target = ["1", "2", "3"]
+				.iter()
+				.find_map(|key| target_data.get(*key))
+				.cloned()
+				.unwrap_or_default();
+			Matcher {
+				one_handle: Some(Box::new(move |ele, _| {
+					if target.is_empty() {
+						return false;
+					}
+					if ele.get_attribute("id").map(|id| id.is_str(&target)) == Some(true) {
+						return true;
+					}
+					// when no element has the id, the target is the first 'a' element with the name
+					let is_named_anchor = |cur: &dyn IElementTrait| {
+						get_tag_name(cur) == "a"
+							&& cur.get_attribute("name").map(|name| name.is_str(&target)) == Some(true)
+					};
+					if !is_named_anchor(ele) {
+						return false;
+					}
+					if let Some(doc) = ele.owner_document() {
+						if doc.get_element_by_id(&target).is_some() {
+							return false;
+						}
+					}
+					let mut first_anchor = None;
+					if let Some(root) = ele.root_element() {
+						loop_descendants(&*root, &mut |cur| {
+							if is_named_anchor(&**cur) {
+								first_anchor = Some(cur.cloned());
+								return true;
+							}
+							false
+						});
+					}
+					first_anchor.map(|anchor| ele.is(&anchor)).unwrap_or(false)
+				})),
+				..Default::default()
+			}
+		}),
 	);
 	rules.push(rule.into());
 }
@@ -904,16 +1385,269 @@ fn pseudo_has(rules: &mut Vec<RuleItem>) {
 		selector,
 		PRIORITY,
 		Box::new(|data: MatchedQueue| {
-			let selector = data[2].chars.iter().collect::<String>();
+			let selector = parse_nested(&data, 2).expect("The nested selector has been checked");
 			Matcher {
-				all_handle: Some(Box::new(move |eles: &Elements, _| eles.has(&selector))),
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					eles.has_selector(&selector)
+				})),
 				..Default::default()
 			}
 		}),
 	);
+	rules.push(with_nested_checker(rule, 2));
+}
+
+/// pseudo selector: `:parent`
+fn pseudo_parent(rules: &mut Vec<RuleItem>) {
+	// the inverse of `:empty`
+	let selector = ":parent";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| !is_empty_element(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+// get the value of the inline style property, the last declaration wins
+fn get_inline_style(ele: &dyn IElementTrait, property: &str) -> Option<String> {
+	let style = match ele.get_attribute("style") {
+		Some(IAttrValue::Value(style, _)) => style,
+		_ => return None,
+	};
+	style.rsplit(';').find_map(|declaration| {
+		let (name, value) = declaration.split_once(':')?;
+		if !name.trim().eq_ignore_ascii_case(property) {
+			return None;
+		}
+		let value = value.trim().to_ascii_lowercase();
+		let value = value.trim_end_matches("!important").trim_end();
+		Some(String::from(value))
+	})
+}
+
+// the 'display' is 'none' if the element has the 'hidden' attribute or the inline style 'display:none'
+fn get_own_display(ele: &dyn IElementTrait) -> Option<String> {
+	if ele.has_attribute("hidden") || get_inline_style(ele, "display").as_deref() == Some("none") {
+		return Some(String::from("none"));
+	}
+	None
+}
+
+// the inline style 'visibility' is inherited, but can be overridden by the descendants
+fn get_own_visibility(ele: &dyn IElementTrait) -> Option<String> {
+	get_inline_style(ele, "visibility")
+		.filter(|visibility| matches!(visibility.as_str(), "visible" | "hidden" | "collapse"))
+}
+
+// filter the visible or hidden elements by the heuristics of a static document
+fn filter_visible<'r>(eles: &Elements<'r>, visible: bool) -> Elements<'r> {
+	let mut result = Elements::with_capacity(DEF_NODES_LEN);
+	let mut display_cache: HashMap<usize, String> = HashMap::with_capacity(DEF_NODES_LEN);
+	let mut visibility_cache: HashMap<usize, String> = HashMap::with_capacity(DEF_NODES_LEN);
+	for ele in eles.get_ref() {
+		let is_hidden = match get_tag_name(&**ele).as_str() {
+			// the elements never rendered
+			"head" | "title" | "meta" | "link" | "base" | "script" | "style" | "template" => true,
+			"input" if get_input_type(&**ele) == "hidden" => true,
+			_ => {
+				resolve_inherited(
+					&**ele,
+					&mut display_cache,
+					get_own_display,
+					&mut String::new,
+				) == "none"
+					|| resolve_inherited(
+						&**ele,
+						&mut visibility_cache,
+						get_own_visibility,
+						&mut || String::from("visible"),
+					) != "visible"
+			}
+		};
+		if is_hidden != visible {
+			result.push(ele.cloned());
+		}
+	}
+	result
+}
+
+/// pseudo selector: `:visible`, `:hidden`
+fn pseudo_visible(rules: &mut Vec<RuleItem>) {
+	let rule = RuleDefItem(
+		":visible",
+		":visible",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			all_handle: Some(Box::new(|eles: &Elements, _| filter_visible(eles, true))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+	let rule = RuleDefItem(
+		":hidden",
+		":hidden",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			all_handle: Some(Box::new(|eles: &Elements, _| filter_visible(eles, false))),
+			..Default::default()
+		}),
+	);
 	rules.push(rule.into());
 }
 
+// get the index parameter of the positional selectors, e.g. `-1` of `:eq(-1)`
+fn get_positional_index(data: &MatchedQueue) -> Result<isize, String> {
+	match data.iter().find(|matched| matched.name == "regexp") {
+		Some(matched) => {
+			let index = matched.chars.iter().collect::<String>();
+			index
+				.parse::<isize>()
+				.map_err(|_| format!("The index '{}' is out of range", index))
+		}
+		None => Ok(0),
+	}
+}
+
+// positional selectors filter the matched set by the index in it, not the sibling position
+fn make_positional(
+	name: &'static str,
+	selector: &'static str,
+	is_allowed: fn(index: isize, total: isize, n: isize) -> bool,
+) -> RuleItem {
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(move |data: MatchedQueue| {
+			let n = get_positional_index(&data).expect("The index has been checked");
+			Matcher {
+				all_handle: Some(Box::new(move |eles: &Elements, _| {
+					let total = eles.length() as isize;
+					// the negative index counts from the end
+					let n = if n < 0 { n + total } else { n };
+					let mut result = Elements::with_capacity(DEF_NODES_LEN);
+					for (index, ele) in eles.get_ref().iter().enumerate() {
+						if is_allowed(index as isize, total, n) {
+							result.push(ele.cloned());
+						}
+					}
+					result
+				})),
+				positional: true,
+				..Default::default()
+			}
+		}),
+	);
+	let mut rule: RuleItem = rule.into();
+	rule.rule.checker = Some(Box::new(|data: &MatchedQueue| {
+		get_positional_index(data).map(|_| ())
+	}));
+	rule
+}
+
+/// pseudo selector: `:first`, `:last`, `:even`, `:odd`, `:eq`, `:gt`, `:lt`
+fn pseudo_positional(rules: &mut Vec<RuleItem>) {
+	rules.push(make_positional(":first", ":first", |index, _, _| {
+		index == 0
+	}));
+	rules.push(make_positional(":last", ":last", |index, total, _| {
+		index == total - 1
+	}));
+	rules.push(make_positional(":even", ":even", |index, _, _| {
+		index % 2 == 0
+	}));
+	rules.push(make_positional(":odd", ":odd", |index, _, _| {
+		index % 2 == 1
+	}));
+	rules.push(make_positional(
+		":eq",
+		r##":eq({spaces}{regexp#[-+]?\d+#}{spaces})"##,
+		|index, _, n| index == n,
+	));
+	rules.push(make_positional(
+		":gt",
+		r##":gt({spaces}{regexp#[-+]?\d+#}{spaces})"##,
+		|index, _, n| index > n,
+	));
+	rules.push(make_positional(
+		":lt",
+		r##":lt({spaces}{regexp#[-+]?\d+#}{spaces})"##,
+		|index, _, n| index < n,
+	));
+}
+
+// check if the option is selected, include the default option of a single select
+fn is_option_selected(ele: &dyn IElementTrait) -> bool {
+	let option_tag = ['o', 'p', 't', 'i', 'o', 'n'];
+	let select_tag = ['s', 'e', 'l', 'e', 'c', 't'];
+	let is_selected = ele.has_attribute("selected");
+	if is_selected {
+		// if the option tag has 'selected' attribute
+		true
+	} else {
+		// check if is the default option
+		// 1. under the parent 'select' element, without nested tags
+		// 2. the 'select' is not a multiple select
+		// 3. the 'option' is the first 'option' tag
+		// 4. the 'select' has no selected 'option'
+		if let Some(parent) = &ele.parent() {
+			// check condition 1 & 2
+			if is_equal_chars(&parent.tag_names(), &select_tag) && !parent.has_attribute("multiple") {
+				// check if is the first option, condition 3
+				let mut prev = ele.previous_element_sibling();
+				while let Some(prev_ele) = &prev {
+					if is_equal_chars(&prev_ele.tag_names(), &option_tag) {
+						return false;
+					}
+					prev = prev_ele.previous_element_sibling();
+				}
+				// check if the select has selected option, condition 4
+				fn check_selected_option(ele: &BoxDynElement, option_tag: &[char]) -> bool {
+					if is_equal_chars(&ele.tag_names(), option_tag) {
+						return ele.has_attribute("selected");
+					} else {
+						// check the childs
+						let total = ele.child_nodes_length();
+						for index in 0..total {
+							let node = ele
+								.child_nodes_item(index)
+								.expect("Child nodes item index must less than total");
+							if matches!(node.node_type(), INodeType::Element) {
+								let child = node
+									.typed()
+									.into_element()
+									.expect("Call `typed` for element ele.");
+								if check_selected_option(&child, option_tag) {
+									return true;
+								}
+							}
+						}
+					}
+					false
+				}
+				// check the next siblings
+				let mut next = ele.next_element_sibling();
+				while let Some(next_ele) = &next {
+					if check_selected_option(next_ele, &option_tag) {
+						return false;
+					}
+					next = next_ele.next_element_sibling();
+				}
+				// now the option is default option
+				return true;
+			}
+		}
+		// not selected option
+		false
+	}
+}
+
 /// pseudo selector: `:checked`
 fn pseudo_checked(rules: &mut Vec<RuleItem>) {
 	let selector = ":checked";
@@ -927,7 +1661,6 @@ fn pseudo_checked(rules: &mut Vec<RuleItem>) {
 				let tag_name = ele.tag_names();
 				let input_tag = ['i', 'n', 'p', 'u', 't'];
 				let option_tag = ['o', 'p', 't', 'i', 'o', 'n'];
-				let select_tag = ['s', 'e', 'l', 'e', 'c', 't'];
 				if is_equal_chars(&tag_name, &input_tag) {
 					// an input element that with type 'checkbox' or 'radio'
 					if let Some(IAttrValue::Value(input_type, _)) = ele.get_attribute("type") {
@@ -939,68 +1672,7 @@ fn pseudo_checked(rules: &mut Vec<RuleItem>) {
 					}
 					false
 				} else if is_equal_chars(&tag_name, &option_tag) {
-					let is_selected = ele.has_attribute("selected");
-					if is_selected {
-						// if the option tag has 'selected' attribute
-						true
-					} else {
-						// check if is the default option
-						// 1. under the parent 'select' element, without nested tags
-						// 2. the 'select' is not a multiple select
-						// 3. the 'option' is the first 'option' tag
-						// 4. the 'select' has no selected 'option'
-						if let Some(parent) = &ele.parent() {
-							// check condition 1 & 2
-							if is_equal_chars(&parent.tag_names(), &select_tag)
-								&& !parent.has_attribute("multiple")
-							{
-								// check if is the first option, condition 3
-								let mut prev = ele.previous_element_sibling();
-								while let Some(prev_ele) = &prev {
-									if is_equal_chars(&prev_ele.tag_names(), &option_tag) {
-										return false;
-									}
-									prev = prev_ele.previous_element_sibling();
-								}
-								// check if the select has selected option, condition 4
-								fn check_selected_option(ele: &BoxDynElement, option_tag: &[char]) -> bool {
-									if is_equal_chars(&ele.tag_names(), option_tag) {
-										return ele.has_attribute("selected");
-									} else {
-										// check the childs
-										let total = ele.child_nodes_length();
-										for index in 0..total {
-											let node = ele
-												.child_nodes_item(index)
-												.expect("Child nodes item index must less than total");
-											if matches!(node.node_type(), INodeType::Element) {
-												let child = node
-													.typed()
-													.into_element()
-													.expect("Call `typed` for element ele.");
-												if check_selected_option(&child, option_tag) {
-													return true;
-												}
-											}
-										}
-									}
-									false
-								}
-								// check the next siblings
-								let mut next = ele.next_element_sibling();
-								while let Some(next_ele) = &next {
-									if check_selected_option(next_ele, &option_tag) {
-										return false;
-									}
-									next = next_ele.next_element_sibling();
-								}
-								// now the option is default option
-								return true;
-							}
-						}
-						// not selected option
-						false
-					}
+					is_option_selected(ele)
 				} else {
 					// need loop the child
 					false
@@ -1012,6 +1684,436 @@ fn pseudo_checked(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+// -----------form state selectors----------
+
+// the lowercase tag name
+fn get_tag_name(ele: &dyn IElementTrait) -> String {
+	ele.tag_names().into_iter().collect()
+}
+
+// the lowercase attribute value, the attribute without value is an empty string
+fn get_lower_attr(ele: &dyn IElementTrait, name: &str) -> Option<String> {
+	ele.get_attribute(name).map(|value| match value {
+		IAttrValue::Value(value, _) => value.trim().to_ascii_lowercase(),
+		IAttrValue::True => String::new(),
+	})
+}
+
+// the lowercase input type, default is 'text'
+fn get_input_type(ele: &dyn IElementTrait) -> String {
+	get_lower_attr(ele, "type")
+		.filter(|input_type| !input_type.is_empty())
+		.unwrap_or_else(|| String::from("text"))
+}
+
+// the parent element, exclude the abstract root
+fn get_parent_element<'b>(ele: &dyn IElementTrait) -> Option<BoxDynElement<'b>> {
+	ele.parent().filter(|parent| !parent.is_root_element())
+}
+
+// loop the descendant elements in tree order, stop when the handle returns true
+fn loop_descendants(
+	ele: &dyn IElementTrait,
+	handle: &mut dyn FnMut(&BoxDynElement) -> bool,
+) -> bool {
+	for child in ele.children().get_ref() {
+		if handle(child) || loop_descendants(&**child, handle) {
+			return true;
+		}
+	}
+	false
+}
+
+// the form owner is the 'form' element with the id of the 'form' attribute, or the nearest ancestor 'form' element
+fn get_form_owner<'b>(ele: &dyn IElementTrait) -> Option<BoxDynElement<'b>> {
+	let is_form_associated = matches!(
+		get_tag_name(ele).as_str(),
+		"button" | "fieldset" | "input" | "object" | "output" | "select" | "textarea"
+	);
+	if is_form_associated {
+		if let Some(form_id) = ele.get_attribute("form") {
+			// the element has no form owner if the id is not of a 'form' element
+			return match form_id {
+				IAttrValue::Value(id, _) if !id.is_empty() => ele
+					.owner_document()
+					.and_then(|doc| doc.get_element_by_id(&id))
+					.filter(|form| get_tag_name(&**form) == "form"),
+				_ => None,
+			};
+		}
+	}
+	let mut parent = get_parent_element(ele);
+	while let Some(cur) = parent {
+		if get_tag_name(&*cur) == "form" {
+			return Some(cur);
+		}
+		parent = get_parent_element(&*cur);
+	}
+	None
+}
+
+// check if two elements have the same form owner
+fn is_same_form_owner(owner: &Option<BoxDynElement>, ele: &dyn IElementTrait) -> bool {
+	match (owner, &get_form_owner(ele)) {
+		(Some(owner), Some(cur)) => owner.is(cur),
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+// check if the element is a descendant of a disabled 'fieldset', but not in its first 'legend'
+fn is_in_disabled_fieldset(ele: &dyn IElementTrait) -> bool {
+	let mut child = ele.cloned();
+	while let Some(parent) = get_parent_element(&*child) {
+		if get_tag_name(&*parent) == "fieldset" && parent.has_attribute("disabled") {
+			let first_legend = parent
+				.children()
+				.get_ref()
+				.iter()
+				.find(|ele| get_tag_name(&***ele) == "legend")
+				.map(|ele| ele.cloned());
+			match first_legend {
+				Some(legend) if legend.is(&child) => {}
+				_ => return true,
+			}
+		}
+		child = parent;
+	}
+	false
+}
+
+// check if the element is disabled
+fn is_disabled(ele: &dyn IElementTrait) -> bool {
+	match get_tag_name(ele).as_str() {
+		"button" | "input" | "select" | "textarea" | "fieldset" => {
+			ele.has_attribute("disabled") || is_in_disabled_fieldset(ele)
+		}
+		"optgroup" => ele.has_attribute("disabled"),
+		"option" => {
+			ele.has_attribute("disabled")
+				|| get_parent_element(ele)
+					.map(|parent| get_tag_name(&*parent) == "optgroup" && parent.has_attribute("disabled"))
+					.unwrap_or(false)
+		}
+		_ => false,
+	}
+}
+
+// check if the element is required, the 'required' attribute doesn't apply to some input types
+fn is_required(ele: &dyn IElementTrait) -> bool {
+	match get_tag_name(ele).as_str() {
+		"input" => {
+			ele.has_attribute("required")
+				&& !matches!(
+					get_input_type(ele).as_str(),
+					"hidden" | "range" | "color" | "submit" | "image" | "reset" | "button"
+				)
+		}
+		"select" | "textarea" => ele.has_attribute("required"),
+		_ => false,
+	}
+}
+
+// check if the element is editable by the user
+fn is_read_write(ele: &dyn IElementTrait) -> bool {
+	let is_mutable = match get_tag_name(ele).as_str() {
+		"input" => matches!(
+			get_input_type(ele).as_str(),
+			"text"
+				| "search"
+				| "url"
+				| "tel"
+				| "email"
+				| "password"
+				| "date"
+				| "month"
+				| "week"
+				| "time"
+				| "datetime-local"
+				| "number"
+		),
+		"textarea" => true,
+		_ => false,
+	};
+	if is_mutable && !ele.has_attribute("readonly") && !is_disabled(ele) {
+		return true;
+	}
+	// the editing host, the 'contenteditable' attribute with an invalid value inherits the parent's state
+	let mut cur = Some(ele.cloned());
+	while let Some(cur_ele) = cur {
+		if let Some(editable) = get_lower_attr(&*cur_ele, "contenteditable") {
+			match editable.as_str() {
+				"" | "true" | "plaintext-only" => return true,
+				"false" => return false,
+				_ => {}
+			}
+		}
+		cur = get_parent_element(&*cur_ele);
+	}
+	false
+}
+
+// check if the element is a submit button
+fn is_submit_button(ele: &dyn IElementTrait) -> bool {
+	match get_tag_name(ele).as_str() {
+		// the invalid type of 'button' is 'submit'
+		"button" => !matches!(
+			get_lower_attr(ele, "type").as_deref(),
+			Some("reset") | Some("button")
+		),
+		"input" => matches!(get_input_type(ele).as_str(), "submit" | "image"),
+		_ => false,
+	}
+}
+
+// the name of the radio, the radio without name is a group itself
+fn get_radio_name(ele: &dyn IElementTrait) -> Option<String> {
+	if get_tag_name(ele) != "input" || get_input_type(ele) != "radio" {
+		return None;
+	}
+	match ele.get_attribute("name") {
+		Some(IAttrValue::Value(name, _)) if !name.is_empty() => Some(name),
+		_ => None,
+	}
+}
+
+type CheckedRadioGroups = HashMap<usize, HashSet<(Option<usize>, String)>>;
+
+// the radio groups that have a checked radio in the document, the groups are keyed by the form owner and the name
+fn get_checked_radio_groups(root: &dyn IElementTrait) -> HashSet<(Option<usize>, String)> {
+	let mut groups = HashSet::new();
+	loop_descendants(root, &mut |cur| {
+		if cur.has_attribute("checked") {
+			if let Some(name) = get_radio_name(&**cur) {
+				let owner = get_form_owner(&**cur).and_then(|form| form.node_id());
+				groups.insert((owner, name));
+			}
+		}
+		false
+	});
+	groups
+}
+
+// check if the element is a radio and no radio of its group is checked
+fn is_indeterminate_radio(
+	ele: &dyn IElementTrait,
+	checked_groups: &mut CheckedRadioGroups,
+) -> bool {
+	if ele.has_attribute("checked") || get_input_type(ele) != "radio" {
+		return false;
+	}
+	let name = match get_radio_name(ele) {
+		Some(name) => name,
+		None => return true,
+	};
+	let root = match ele.root_element() {
+		Some(root) => root,
+		None => return true,
+	};
+	let owner = get_form_owner(ele);
+	let root_id = match root.node_id() {
+		Some(root_id) => root_id,
+		None => {
+			// the nodes without id can't be grouped, find the checked radio of the group one by one
+			return !loop_descendants(&*root, &mut |cur| {
+				cur.has_attribute("checked")
+					&& get_radio_name(&**cur).as_ref() == Some(&name)
+					&& is_same_owner(&get_form_owner(&**cur), &owner)
+			});
+		}
+	};
+	let groups = checked_groups
+		.entry(root_id)
+		.or_insert_with(|| get_checked_radio_groups(&*root));
+	let owner = owner.and_then(|form| form.node_id());
+	!groups.contains(&(owner, name))
+}
+
+// check if the elements have the same form owner
+fn is_same_owner(owner: &Option<BoxDynElement>, other: &Option<BoxDynElement>) -> bool {
+	match (owner, other) {
+		(Some(owner), Some(other)) => owner.is(other),
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+/// pseudo selector: `:disabled`, `:enabled`
+fn pseudo_disabled(rules: &mut Vec<RuleItem>) {
+	let rule = RuleDefItem(
+		":disabled",
+		":disabled",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| is_disabled(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+	let rule = RuleDefItem(
+		":enabled",
+		":enabled",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| {
+				matches!(
+					get_tag_name(ele).as_str(),
+					"button" | "input" | "select" | "textarea" | "optgroup" | "option" | "fieldset"
+				) && !is_disabled(ele)
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:required`, `:optional`
+fn pseudo_required(rules: &mut Vec<RuleItem>) {
+	let rule = RuleDefItem(
+		":required",
+		":required",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| is_required(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+	let rule = RuleDefItem(
+		":optional",
+		":optional",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| {
+				matches!(get_tag_name(ele).as_str(), "input" | "select" | "textarea") && !is_required(ele)
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:read-only`, `:read-write`
+fn pseudo_read_write(rules: &mut Vec<RuleItem>) {
+	let rule = RuleDefItem(
+		":read-only",
+		":read-only",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| !is_read_write(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+	let rule = RuleDefItem(
+		":read-write",
+		":read-write",
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| is_read_write(ele))),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:selected`
+fn pseudo_selected(rules: &mut Vec<RuleItem>) {
+	let selector = ":selected";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| {
+				get_tag_name(ele) == "option" && is_option_selected(ele)
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:indeterminate`
+fn pseudo_indeterminate(rules: &mut Vec<RuleItem>) {
+	let selector = ":indeterminate";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|_| Matcher {
+			all_handle: Some(Box::new(|eles: &Elements, _| {
+				let mut result = Elements::with_capacity(DEF_NODES_LEN);
+				// the checked radio groups of each document, collected once by the root's node id
+				let mut checked_groups: CheckedRadioGroups = HashMap::new();
+				for ele in eles.get_ref() {
+					// the checkbox's indeterminate state can only be set by script, so it's never matched
+					let is_indeterminate = match get_tag_name(&**ele).as_str() {
+						"progress" => !ele.has_attribute("value"),
+						"input" => is_indeterminate_radio(&**ele, &mut checked_groups),
+						_ => false,
+					};
+					if is_indeterminate {
+						result.push(ele.cloned());
+					}
+				}
+				result
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
+/// pseudo selector: `:default`
+fn pseudo_default(rules: &mut Vec<RuleItem>) {
+	let selector = ":default";
+	let name = selector;
+	let rule = RuleDefItem(
+		name,
+		selector,
+		PRIORITY,
+		Box::new(|_| Matcher {
+			one_handle: Some(Box::new(|ele, _| match get_tag_name(ele).as_str() {
+				"option" => ele.has_attribute("selected"),
+				"input" if matches!(get_input_type(ele).as_str(), "checkbox" | "radio") => {
+					ele.has_attribute("checked")
+				}
+				_ => {
+					// the default button is the first submit button of its form owner
+					if !is_submit_button(ele) {
+						return false;
+					}
+					let owner = match get_form_owner(ele) {
+						Some(form) => Some(form),
+						None => return false,
+					};
+					// the buttons associated by the 'form' attribute may be out of the form
+					let root = match ele.root_element() {
+						Some(root) => root,
+						None => return false,
+					};
+					let mut default_button = None;
+					loop_descendants(&*root, &mut |cur| {
+						if is_submit_button(&**cur) && is_same_form_owner(&owner, &**cur) {
+							default_button = Some(cur.cloned());
+							return true;
+						}
+						false
+					});
+					default_button
+						.map(|button| button.is(&ele.cloned()))
+						.unwrap_or(false)
+				}
+			})),
+			..Default::default()
+		}),
+	);
+	rules.push(rule.into());
+}
+
 /// pseudo selector: `:header`
 fn pseudo_alias_header(rules: &mut Vec<RuleItem>) {
 	let (selector, alias) = SELECTOR_ALIAS_NAME_HEADER;
@@ -1051,9 +2153,34 @@ fn pseudo_alias_submit(rules: &mut Vec<RuleItem>) {
 	rules.push(rule.into());
 }
 
+/// pseudo selector: `:text`, `:password`, `:radio`, `:checkbox`, `:file`, `:image`, `:button`, `:reset`
+fn pseudo_alias_input_types(rules: &mut Vec<RuleItem>) {
+	let aliases = [
+		SELECTOR_ALIAS_NAME_TEXT,
+		SELECTOR_ALIAS_NAME_PASSWORD,
+		SELECTOR_ALIAS_NAME_RADIO,
+		SELECTOR_ALIAS_NAME_CHECKBOX,
+		SELECTOR_ALIAS_NAME_FILE,
+		SELECTOR_ALIAS_NAME_IMAGE,
+		SELECTOR_ALIAS_NAME_BUTTON,
+		SELECTOR_ALIAS_NAME_RESET,
+	];
+	for (selector, alias) in aliases {
+		let name = selector;
+		let rule = RuleDefItem(
+			name,
+			selector,
+			PRIORITY,
+			Box::new(move |_| Rule::make_alias(alias)),
+		);
+		rules.push(rule.into());
+	}
+}
+
 pub fn init(rules: &mut Vec<RuleItem>) {
 	pseudo_root(rules);
 	pseudo_empty(rules);
+	pseudo_blank(rules);
 	// :first-child, :last-child
 	pseudo_first_child(rules);
 	pseudo_last_child(rules);
@@ -1062,6 +2189,9 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 	// :nth-child,:nth-last-child
 	pseudo_nth_child(rules);
 	pseudo_nth_last_child(rules);
+	// :nth-child(An+B of S), :nth-last-child(An+B of S)
+	pseudo_nth_child_of(rules);
+	pseudo_nth_last_child_of(rules);
 	// :first-of-type,:last-of-type
 	pseudo_first_of_type(rules);
 	pseudo_last_of_type(rules);
@@ -1072,17 +2202,47 @@ pub fn init(rules: &mut Vec<RuleItem>) {
 	pseudo_only_of_type(rules);
 	// :not
 	pseudo_not(rules);
+	// :is, :where
+	pseudo_is(rules);
+	pseudo_where(rules);
 	// :contains
 	pseudo_contains(rules);
+	// :icontains, :contains-own
+	pseudo_icontains(rules);
+	pseudo_contains_own(rules);
+	// :matches
+	pseudo_matches(rules);
+	// :lang, :dir
+	pseudo_lang(rules);
+	pseudo_dir(rules);
+	// :any-link, :link, :target
+	pseudo_any_link(rules);
+	pseudo_target(rules);
 	// ---- jquery selectors -----
 	// :has
 	pseudo_has(rules);
+	// :parent
+	pseudo_parent(rules);
+	// :visible, :hidden
+	pseudo_visible(rules);
 	// :checked
 	pseudo_checked(rules);
+	// :disabled, :enabled, :required, :optional, :read-only, :read-write
+	pseudo_disabled(rules);
+	pseudo_required(rules);
+	pseudo_read_write(rules);
+	// :selected, :indeterminate, :default
+	pseudo_selected(rules);
+	pseudo_indeterminate(rules);
+	pseudo_default(rules);
 	// :header alias
 	pseudo_alias_header(rules);
 	// :input alias
 	pseudo_alias_input(rules);
 	// :submit alias
 	pseudo_alias_submit(rules);
+	// :text, :password, :radio, :checkbox, :file, :image, :button, :reset alias
+	pseudo_alias_input_types(rules);
+	// positional selectors, must after `:first-child`, `:last-child` and so on
+	pseudo_positional(rules);
 }
@@ -0,0 +1,62 @@
+use super::source::{join_segments, SegmentSource, Specificity};
+use super::Combinator;
+use crate::mesdoc::interface::{BoxDynElement, Elements};
+use std::fmt;
+
+/// The explanation of matching an element by a selector in the selector list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+	/// The selector, e.g. `div.list > a.item`.
+	pub selector: String,
+	/// The specificity of the selector.
+	pub specificity: Specificity,
+	/// The compound selectors and the combinators before them, e.g. `(ChildrenAll, "div.list")`, `(Children, "a.item")`.
+	pub segments: Vec<(Combinator, String)>,
+	/// The index of the compound selector that failed, the segments are checked from right to left,
+	/// so the segments after it are matched. `None` means the element is matched by the selector.
+	pub failed: Option<usize>,
+}
+
+impl Explanation {
+	pub(crate) fn new(segments: &[SegmentSource], ele: &BoxDynElement) -> Self {
+		let eles = Elements::with_node(ele);
+		let failed = (0..segments.len()).rev().find(|&index| {
+			let context = join_segments(&segments[index..], index == 0);
+			!eles.is(&context)
+		});
+		Explanation {
+			selector: join_segments(segments, true),
+			specificity: segments
+				.iter()
+				.fold(Specificity::default(), |total, segment| {
+					total + segment.get_specificity()
+				}),
+			segments: segments
+				.iter()
+				.map(|segment| (segment.comb, segment.to_context()))
+				.collect(),
+			failed,
+		}
+	}
+	/// Check if the element is matched by the selector.
+	pub fn is_matched(&self) -> bool {
+		self.failed.is_none()
+	}
+	/// Get the compound selector that failed.
+	pub fn failed_segment(&self) -> Option<&str> {
+		self.failed.map(|index| self.segments[index].1.as_str())
+	}
+}
+
+impl fmt::Display for Explanation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.failed_segment() {
+			Some(segment) => write!(
+				f,
+				"`{}` {} failed at `{}`",
+				self.selector, self.specificity, segment
+			),
+			None => write!(f, "`{}` {} matched", self.selector, self.specificity),
+		}
+	}
+}
@@ -1,20 +1,29 @@
+mod explain;
 pub mod pattern;
 pub mod rule;
+mod source;
 use self::{pattern::BoxDynPattern, rule::Matcher};
+use crate::mesdoc::interface::BoxDynElement;
+use crate::mesdoc::rules::namespace::{get_declared_namespaces, with_namespaces};
 use crate::mesdoc::{constants::NAME_SELECTOR_ALL, error::Error};
-use lazy_static::lazy_static;
+pub use explain::Explanation;
 pub use pattern::MatchedQueue;
 use pattern::{exec, Matched};
-use rule::{Rule, RULES};
+use rule::{get_rules, Rule};
+pub use source::Specificity;
+use source::{join_segments, SegmentSource};
 use std::{
+	fmt,
 	str::FromStr,
-	sync::{Arc, Mutex},
+	sync::{Arc, OnceLock},
 };
 
-lazy_static! {
-	static ref SPLITTER: Mutex<Vec<BoxDynPattern>> =
-		Mutex::new(Rule::get_queues(r##"{regexp#(\s*[>,~+]\s*|\s+)#}"##));
-	static ref ALL_RULE: Mutex<Option<Arc<Rule>>> = Mutex::new(None);
+static SPLITTER: OnceLock<Vec<BoxDynPattern>> = OnceLock::new();
+static ALL_RULE: OnceLock<Arc<Rule>> = OnceLock::new();
+
+// the combinator splitter
+fn get_splitter() -> &'static [BoxDynPattern] {
+	SPLITTER.get_or_init(|| Rule::get_queues(r##"{regexp#(\s*[>,~+]\s*|\s+)#}"##))
 }
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Combinator {
@@ -78,6 +87,9 @@ pub struct QueryProcess {
 #[derive(Default, Debug)]
 pub struct Selector {
 	pub process: Vec<QueryProcess>,
+	// the compound selectors of each selector in the list, for the specificity and the explanation,
+	// only kept by `Selector::with_sources`
+	pub(crate) sources: Vec<Vec<SegmentSource>>,
 }
 
 type SelectorGroupsItem = Vec<Vec<SelectorSegment>>;
@@ -86,9 +98,17 @@ impl Selector {
 	pub fn new() -> Self {
 		Selector {
 			process: Vec::with_capacity(1),
+			sources: Vec::with_capacity(1),
 		}
 	}
 	pub fn from_str(context: &str, use_lookup: bool) -> Result<Self, Error> {
+		Selector::parse(context, use_lookup, false)
+	}
+	// parse the selector and keep the sources, for the compiled selector
+	pub(crate) fn with_sources(context: &str) -> Result<Self, Error> {
+		Selector::parse(context, false, true)
+	}
+	fn parse(context: &str, use_lookup: bool, keep_sources: bool) -> Result<Self, Error> {
 		let chars: Vec<char> = context.chars().collect();
 		let total_len = chars.len();
 		let mut selector = Selector::new();
@@ -98,13 +118,17 @@ impl Selector {
 			let mut prev_in = PrevInSelector::Begin;
 			let mut last_in = prev_in;
 			let mut groups: SelectorGroups = Vec::new();
-			let splitter = SPLITTER.lock().unwrap();
-			let rules = RULES.lock().unwrap();
+			let splitter = get_splitter();
+			let rules = get_rules();
+			let rules = &rules.1;
 			Selector::add_group(&mut groups);
+			if keep_sources {
+				selector.sources.push(Vec::with_capacity(2));
+			}
 			while index < total_len {
 				let next_chars = &chars[index..];
 				// first check if combinator
-				if let Some((matched, len, _)) = Rule::exec_queues(&splitter, next_chars) {
+				if let Some((matched, len, _)) = Rule::exec_queues(splitter, next_chars) {
 					let op = matched[0].chars.iter().collect::<String>();
 					let op = op.trim();
 					if prev_in == PrevInSelector::Splitter {
@@ -129,6 +153,9 @@ impl Selector {
 							});
 						}
 						Selector::add_group(&mut groups);
+						if keep_sources {
+							selector.sources.push(Vec::with_capacity(2));
+						}
 						comb = Combinator::ChildrenAll;
 					} else {
 						comb = Combinator::from(op);
@@ -137,6 +164,10 @@ impl Selector {
 					if op.is_empty() {
 						last_in = prev_in;
 						prev_in = PrevInSelector::Splitter;
+					} else if op == "," {
+						// a new selector begins, allow it starts with a combinator as the first selector
+						prev_in = PrevInSelector::Begin;
+						last_in = prev_in;
 					} else {
 						prev_in = PrevInSelector::Splitter;
 						last_in = prev_in;
@@ -153,13 +184,30 @@ impl Selector {
 					last_in = prev_in;
 				}
 				let mut finded = false;
-				for (_, r) in rules.iter() {
+				let start_index = index;
+				for (name, r) in rules.iter() {
 					if let Some((mut matched, len, queue_num)) = r.exec(next_chars) {
+						let queues = &r.queues;
+						if queue_num < queues.len() && !queues[queue_num].is_nested() {
+							// partially matched, try the next rule, e.g. ':nth-child(2n)' and ':nth-child(2n of .a)'
+							continue;
+						}
 						// find the rule
 						index += len;
-						let queues = &r.queues;
 						if queue_num == queues.len() {
+							// check the matched data
+							r.check(&matched).map_err(|reason| Error::InvalidSelector {
+								context: String::from(context),
+								reason,
+							})?;
 							// push to selector
+							selector.add_source(
+								name,
+								&matched,
+								comb,
+								&chars[start_index..index],
+								is_new_item,
+							);
 							Selector::add_group_item(&mut groups, (r.make(matched), comb), is_new_item);
 							finded = true;
 						} else if queues[queue_num].is_nested() {
@@ -167,12 +215,24 @@ impl Selector {
 							let (len, nested_matched) = Selector::parse_until(
 								&chars[index..],
 								&queues[queue_num + 1..],
-								&rules,
-								&splitter,
+								rules,
+								splitter,
 								0,
 							)?;
 							index += len;
 							matched.extend(nested_matched);
+							// check the matched data with the nested selector
+							r.check(&matched).map_err(|reason| Error::InvalidSelector {
+								context: String::from(context),
+								reason,
+							})?;
+							selector.add_source(
+								name,
+								&matched,
+								comb,
+								&chars[start_index..index],
+								is_new_item,
+							);
 							Selector::add_group_item(&mut groups, (r.make(matched), comb), is_new_item);
 							finded = true;
 						}
@@ -216,6 +276,38 @@ impl Selector {
 			}
 		}
 	}
+	// add the rule to the compound selector source, do nothing if the sources are not kept
+	fn add_source(
+		&mut self,
+		name: &str,
+		matched: &MatchedQueue,
+		comb: Combinator,
+		context: &[char],
+		is_new: bool,
+	) {
+		if let Some(segments) = self.sources.last_mut() {
+			if is_new || segments.is_empty() {
+				segments.push(SegmentSource::new(comb));
+			}
+			if let Some(segment) = segments.last_mut() {
+				segment.add_rule(name, matched, context);
+			}
+		}
+	}
+	/// the specificity of each selector in the list
+	pub fn specificity(&self) -> Vec<Specificity> {
+		self
+			.sources
+			.iter()
+			.map(|segments| {
+				segments
+					.iter()
+					.fold(Specificity::default(), |total, segment| {
+						total + segment.get_specificity()
+					})
+			})
+			.collect()
+	}
 	// optimize the parse process
 	fn optimize(&mut self, groups: SelectorGroups, use_lookup: bool) {
 		let mut process: Vec<QueryProcess> = Vec::with_capacity(groups.len());
@@ -223,11 +315,15 @@ impl Selector {
 			// first optimize the chain selectors, the rule who's priority is bigger will apply first
 			let mut max_index: usize = 0;
 			let mut max_priority: u32 = 0;
+			let mut has_positional = false;
 			for (index, r) in group.iter_mut().enumerate() {
 				let mut total_priority = 0;
+				let chain_comb = r[0].1;
 				if r.len() > 1 {
-					let chain_comb = r[0].1;
-					r.sort_by(|a, b| b.0.priority.partial_cmp(&a.0.priority).unwrap());
+					// the positional rules filter the matched set, so only sort the rules between them
+					for rules in r.split_mut(|segment| segment.0.positional) {
+						rules.sort_by(|a, b| b.0.priority.partial_cmp(&a.0.priority).unwrap());
+					}
 					let now_first = &mut r[0];
 					if now_first.1 != chain_comb {
 						now_first.1 = chain_comb;
@@ -238,6 +334,14 @@ impl Selector {
 						}
 					}
 				}
+				if r.iter().any(|segment| segment.0.positional) {
+					has_positional = true;
+					// the positional rule should apply to all the matched elements, not each children group
+					if r[0].0.positional {
+						r[0].1 = Combinator::Chain;
+						r.insert(0, Selector::make_comb_all(chain_comb));
+					}
+				}
 				if use_lookup {
 					total_priority = r.iter().map(|p| p.0.priority).sum();
 					if total_priority > max_priority {
@@ -247,7 +351,8 @@ impl Selector {
 				}
 			}
 			// if the first combinator is child, and the max_index > 1, use the max_index's rule first
-			if use_lookup && max_index > 0 {
+			// the positional rules depend on the matched set, so can't lookup from the middle
+			if use_lookup && max_index > 0 && !has_positional {
 				let is_child = matches!(
 					group[0][0].1,
 					Combinator::Children | Combinator::ChildrenAll
@@ -286,19 +391,26 @@ impl Selector {
 			}
 		}
 	}
+	// check if any selector in the list starts with a combinator except descendant, e.g. '> a', '+ a', '~ a'
+	pub fn is_relative(&self) -> bool {
+		self.process.iter().any(|p| {
+			let v = p.should_in.as_ref().unwrap_or(&p.query);
+			v.first()
+				.map(|rule| rule[0].1 != Combinator::ChildrenAll)
+				.unwrap_or(false)
+		})
+	}
 	// make '*' with combinator
 	pub fn make_comb_all(comb: Combinator) -> SelectorSegment {
-		let mut all_rule = ALL_RULE.lock().unwrap();
-		if all_rule.is_none() {
-			let rules = RULES.lock().unwrap();
-			for (name, rule) in &rules[..] {
-				if *name == NAME_SELECTOR_ALL {
-					*all_rule = Some(Arc::clone(rule));
-					break;
-				}
-			}
-		}
-		let cur_rule = Arc::clone(all_rule.as_ref().expect("All rule must add to rules"));
+		let cur_rule = ALL_RULE.get_or_init(|| {
+			let rules = get_rules();
+			let (_, rule) = rules
+				.1
+				.iter()
+				.find(|(name, _)| *name == NAME_SELECTOR_ALL)
+				.expect("All rule must add to rules");
+			Arc::clone(rule)
+		});
 		let matcher = cur_rule.make(vec![]);
 		(matcher, comb)
 	}
@@ -310,6 +422,7 @@ impl Selector {
 		};
 		Selector {
 			process: vec![process],
+			..Default::default()
 		}
 	}
 	// parse until
@@ -323,32 +436,45 @@ impl Selector {
 		let mut index = 0;
 		let total = chars.len();
 		let mut matched: MatchedQueue = Vec::with_capacity(until.len() + 1);
-		while index < total {
-			let next_chars = &chars[index..];
-			if let Some((_, len, _)) = Rule::exec_queues(splitter, next_chars) {
-				index += len;
-				continue;
-			}
+		loop {
 			let mut finded = false;
-			for (_, r) in rules.iter() {
-				if let Some((_, len, queue_num)) = r.exec(next_chars) {
-					let queues = &r.queues;
-					// find the rule
+			// when reach the end, check if the nested selector is closed
+			if index < total {
+				let next_chars = &chars[index..];
+				if let Some((_, len, _)) = Rule::exec_queues(splitter, next_chars) {
 					index += len;
-					if queue_num == queues.len() {
-						// push to selector
+					continue;
+				}
+				for (_, r) in rules.iter() {
+					if let Some((rule_matched, len, queue_num)) = r.exec(next_chars) {
+						let queues = &r.queues;
+						if queue_num < queues.len() && !queues[queue_num].is_nested() {
+							// partially matched, try the next rule
+							continue;
+						}
+						// find the rule
+						index += len;
+						if queue_num == queues.len() {
+							// check the matched data
+							r.check(&rule_matched)
+								.map_err(|reason| Error::InvalidSelector {
+									context: chars.iter().collect::<String>(),
+									reason,
+								})?;
+						} else {
+							let (nest_count, _) = Selector::parse_until(
+								&chars[index..],
+								&queues[queue_num + 1..],
+								rules,
+								splitter,
+								level + 1,
+							)?;
+							index += nest_count;
+						}
+						// push to selector, then continue to parse the rest
 						finded = true;
-					} else {
-						let (nest_count, _) = Selector::parse_until(
-							&chars[index..],
-							&queues[queue_num + 1..],
-							rules,
-							splitter,
-							level + 1,
-						)?;
-						index += nest_count;
+						break;
 					}
-					break;
 				}
 			}
 			if !finded {
@@ -388,6 +514,19 @@ enum PrevInSelector {
 	Selector,
 }
 
+// print the normalized selector
+impl fmt::Display for Selector {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (index, segments) in self.sources.iter().enumerate() {
+			if index > 0 {
+				f.write_str(", ")?;
+			}
+			f.write_str(&join_segments(segments, true))?;
+		}
+		Ok(())
+	}
+}
+
 impl FromStr for Selector {
 	type Err = Error;
 	fn from_str(selector: &str) -> Result<Self, Self::Err> {
@@ -395,6 +534,111 @@ impl FromStr for Selector {
 	}
 }
 
+/// A selector parsed once and can be reused by the `*_compiled` methods of `Elements`.
+#[derive(Debug)]
+pub struct CompiledSelector {
+	context: String,
+	// use lookup, for `find`
+	pub(crate) lookup: Selector,
+	// don't use lookup, for filter methods
+	pub(crate) filter: Selector,
+	// the declared namespaces when compiled, the nested selectors are parsed again with them
+	namespaces: Vec<(String, String)>,
+}
+
+impl CompiledSelector {
+	pub(crate) fn new(context: &str) -> Result<Self, Error> {
+		Ok(CompiledSelector {
+			context: String::from(context),
+			lookup: Selector::from_str(context, true)?,
+			filter: Selector::with_sources(context)?,
+			namespaces: get_declared_namespaces(),
+		})
+	}
+	/// Get the selector string.
+	pub fn as_str(&self) -> &str {
+		&self.context
+	}
+	// parse the selectors again with the declared namespaces when compiled
+	fn with_compiled_namespaces<F, R>(&self, handle: F) -> R
+	where
+		F: FnOnce() -> R,
+	{
+		let namespaces = self
+			.namespaces
+			.iter()
+			.map(|(prefix, uri)| (prefix.as_str(), uri.as_str()))
+			.collect::<Vec<(&str, &str)>>();
+		with_namespaces(&namespaces, handle)
+	}
+	/// Get the css specificity of each selector in the selector list.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::{BoxDynError, Specificity};
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let selector = Vis::compile("#main ul > li.item:not(.ad), a[href]:where(.nav a)")?;
+	///   assert_eq!(selector.specificity(), vec![Specificity(1, 2, 2), Specificity(0, 1, 1)]);
+	///   assert!(selector.specificity()[0] > selector.specificity()[1]);
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn specificity(&self) -> Vec<Specificity> {
+		self.with_compiled_namespaces(|| self.filter.specificity())
+	}
+	/// Explain how the element is matched by each selector in the selector list,
+	/// the compound selectors are checked from right to left, the first one that can't be matched is reported as failed.
+	///
+	/// ```
+	/// use visdom::Vis;
+	/// use visdom::types::BoxDynError;
+	/// fn main()-> Result<(), BoxDynError>{
+	///   let html = r##"
+	///     <div class="list-new"><ul><li><a class="title">title</a></li></ul></div>
+	///   "##;
+	///   let doc = Vis::load(html)?;
+	///   let title = doc.find("a");
+	///   let selector = Vis::compile("div.list > ul a.title, li > a")?;
+	///   let explanations = selector.explain(title.get(0).unwrap());
+	///   assert_eq!(explanations[0].failed_segment(), Some("div.list"));
+	///   assert_eq!(explanations[0].to_string(), "`div.list > ul a.title` (0, 2, 3) failed at `div.list`");
+	///   assert!(explanations[1].is_matched());
+	///   Ok(())
+	/// }
+	/// ```
+	pub fn explain(&self, ele: &BoxDynElement) -> Vec<Explanation> {
+		self.with_compiled_namespaces(|| {
+			self
+				.filter
+				.sources
+				.iter()
+				.map(|segments| Explanation::new(segments, ele))
+				.collect()
+		})
+	}
+}
+
+/// Print the normalized selector: the whitespaces are collapsed, the combinators are spaced,
+/// the quoted values are changed to double quoted if they have no double quotes and escapes.
+/// The normalized selector can be parsed again, and be printed as the same.
+///
+/// ```
+/// use visdom::Vis;
+/// use visdom::types::BoxDynError;
+/// fn main()-> Result<(), BoxDynError>{
+///   let selector = Vis::compile("ul>li.item  +li:not( .ad ,.hide ) a[ href ^= '/a' i ],p")?;
+///   let normalized = selector.to_string();
+///   assert_eq!(normalized, r#"ul > li.item + li:not(.ad, .hide) a[href^="/a" i], p"#);
+///   assert_eq!(Vis::compile(&normalized)?.to_string(), normalized);
+///   Ok(())
+/// }
+/// ```
+impl fmt::Display for CompiledSelector {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.with_compiled_namespaces(|| self.filter.fmt(f))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::{Combinator, QueryProcess, Selector};
@@ -429,4 +673,40 @@ mod tests {
 	fn test_wrong_combinator_string() {
 		let _: Combinator = "<".into();
 	}
+
+	#[test]
+	fn test_partial_matched_rule() {
+		crate::mesdoc::init();
+		// the rules only matched a part of the patterns are skipped, then try the next rules
+		assert!(Selector::from_str("li:nth-child(2n)", false).is_ok());
+		assert!(Selector::from_str("li:nth-child(2n of .a)", false).is_ok());
+		assert!(Selector::from_str("li:nth-last-child( 1 of :not(.a) )", false).is_ok());
+		// no rule matched all the patterns
+		assert!(Selector::from_str("li:nth-child(2n of)", false).is_err());
+		assert!(Selector::from_str("li:nth-child(2n of .a", false).is_err());
+	}
+
+	#[test]
+	fn test_parse_until() {
+		crate::mesdoc::init();
+		// the nested selectors must be closed
+		assert!(Selector::from_str(":not(.a", false).is_err());
+		assert!(Selector::from_str(":is(:not(.a)", false).is_err());
+		assert!(Selector::from_str("div:has(> b", false).is_err());
+		// the nested selectors followed by other selectors
+		assert!(Selector::from_str(":is(:not(.a) .b)", false).is_ok());
+		assert!(Selector::from_str("div:has(:not(.a) > .b) span", false).is_ok());
+		assert!(Selector::from_str(":not(:is(.a, .b), .c):not(.d)", false).is_ok());
+	}
+
+	#[test]
+	fn test_selector_sources() {
+		crate::mesdoc::init();
+		// the sources are only kept for the compiled selectors
+		let selector = Selector::from_str("ul > li.a, p", true).unwrap();
+		assert!(selector.sources.is_empty());
+		let selector = Selector::with_sources("ul > li.a, p").unwrap();
+		assert_eq!(selector.sources.len(), 2);
+		assert_eq!(selector.to_string(), "ul > li.a, p");
+	}
 }
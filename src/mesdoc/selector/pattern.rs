@@ -1,15 +1,14 @@
 use crate::mesdoc::utils::{divide_isize, is_char_available_in_key, RoundType};
-use lazy_static::lazy_static;
 use regex::Regex;
 use std::borrow::Cow;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::{collections::HashMap, fmt::Debug, usize};
 
-pub type FromParamsFn = Box<dyn Fn(&str, &str) -> Result<BoxDynPattern, String> + Send + 'static>;
-lazy_static! {
-	static ref REGEXS: Mutex<HashMap<String, Arc<Regex>>> = Mutex::new(HashMap::new());
-	static ref PATTERNS: Mutex<HashMap<&'static str, FromParamsFn>> = Mutex::new(HashMap::new());
-}
+pub type FromParamsFn =
+	Box<dyn Fn(&str, &str) -> Result<BoxDynPattern, String> + Send + Sync + 'static>;
+type PatternMap = HashMap<&'static str, FromParamsFn>;
+static REGEXS: OnceLock<RwLock<HashMap<String, Arc<Regex>>>> = OnceLock::new();
+static PATTERNS: OnceLock<PatternMap> = OnceLock::new();
 
 pub type BoxDynPattern = Box<dyn Pattern>;
 
@@ -44,7 +43,7 @@ pub trait Pattern: Send + Sync + Debug {
 
 impl Pattern for char {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let ch = chars[0];
+		let ch = *chars.first()?;
 		if *self == ch {
 			return Some(Matched {
 				chars: vec![ch],
@@ -92,7 +91,7 @@ pub struct Identity;
 impl Pattern for Identity {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
 		let mut result: Vec<char> = Vec::with_capacity(5);
-		let first = chars[0];
+		let first = *chars.first()?;
 		let name: &str = "identity";
 		if !(first.is_ascii_alphabetic() || first == '_') {
 			return None;
@@ -193,14 +192,16 @@ pub struct Nth;
 
 impl Pattern for Nth {
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let rule: RegExp = RegExp {
+		static NTH_RULE: OnceLock<RegExp<'static>> = OnceLock::new();
+		let rule = NTH_RULE.get_or_init(|| RegExp {
 			context: Cow::from(
 				r#"^(?:([-+])?([1-9]\d+|[0-9])?n(?:\s*([+-])\s*([1-9]\d+|[0-9]))?|([-+])?([1-9]\d+|[0-9]))"#,
 			),
-		};
+			..Default::default()
+		});
 		let mut data = HashMap::with_capacity(2);
 		let mut matched_chars: Vec<char> = Vec::new();
-		if let Some(v) = Pattern::matched(&rule, chars) {
+		if let Some(v) = Pattern::matched(rule, chars) {
 			let rule_data = v.data;
 			// when the group index 6,
 			let only_index = rule_data.get("6").is_some();
@@ -344,17 +345,19 @@ impl Nth {
 }
 
 /// RegExp
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct RegExp<'a> {
 	pub context: Cow<'a, str>,
+	// the compiled regex, compiled when first matched
+	pub rule: OnceLock<Arc<Regex>>,
 }
 
 impl<'a> Pattern for RegExp<'a> {
 	/// impl `matched`
 	fn matched(&self, chars: &[char]) -> Option<Matched> {
-		let Self { context } = self;
+		let Self { context, rule } = self;
 		let content = chars.iter().collect::<String>();
-		let rule = RegExp::get_rule(&context);
+		let rule = rule.get_or_init(|| RegExp::get_rule(context));
 		if let Some(caps) = rule.captures(&content) {
 			let total_len = caps[0].chars().count();
 			let mut data = HashMap::with_capacity(caps.len() - 1);
@@ -378,6 +381,7 @@ impl<'a> Pattern for RegExp<'a> {
 		check_params_return(&[s], || {
 			Box::new(RegExp {
 				context: Cow::Owned(p.to_string()),
+				..Default::default()
 			})
 		})
 	}
@@ -387,17 +391,40 @@ impl<'a> RegExp<'a> {
 	pub fn get_rule(context: &str) -> Arc<Regex> {
 		let wrong_regex = format!("Wrong regex context '{}'", context);
 		let last_context = String::from("^") + context;
-		let mut regexs = REGEXS.lock().unwrap();
-		if let Some(rule) = regexs.get(&last_context[..]) {
-			Arc::clone(rule)
-		} else {
-			let key = last_context;
-			let rule = Regex::new(&key).expect(&wrong_regex);
-			let value = Arc::new(rule);
-			let result = Arc::clone(&value);
-			regexs.insert(key, value);
-			result
+		RegExp::try_get_regex(&last_context).expect(&wrong_regex)
+	}
+	/// get a cached regex, return the reason if the regex is malformed
+	pub fn try_get_regex(context: &str) -> Result<Arc<Regex>, String> {
+		let regexs = REGEXS.get_or_init(Default::default);
+		if let Some(rule) = regexs.read().unwrap().get(context) {
+			return Ok(Arc::clone(rule));
 		}
+		let rule = Arc::new(Regex::new(context).map_err(|e| e.to_string())?);
+		let mut regexs = regexs.write().unwrap();
+		Ok(Arc::clone(
+			regexs.entry(String::from(context)).or_insert(rule),
+		))
+	}
+	/// get a regex from a regex literal's source and flags, e.g. `\d+` and `i` of `/\d+/i`
+	pub fn from_literal(source: &str, flags: &str) -> Result<Arc<Regex>, String> {
+		if let Some(flag) = flags
+			.chars()
+			.find(|flag| !matches!(flag, 'i' | 'm' | 's' | 'x'))
+		{
+			return Err(format!(
+				"Unsupported flag '{}' of regex '/{}/{}', only 'i', 'm', 's', 'x' are allowed",
+				flag, source, flags
+			));
+		}
+		// the '/' is escaped in the literal
+		let context = source.replace("\\/", "/");
+		let context = if flags.is_empty() {
+			context
+		} else {
+			format!("(?{}){}", flags, context)
+		};
+		RegExp::try_get_regex(&context)
+			.map_err(|reason| format!("Wrong regex '/{}/{}': {}", source, flags, reason))
 	}
 }
 
@@ -419,8 +446,7 @@ impl Pattern for NestedSelector {
 	}
 }
 
-pub fn add_pattern(name: &'static str, from_handle: FromParamsFn) {
-	let mut patterns = PATTERNS.lock().unwrap();
+pub fn add_pattern(patterns: &mut PatternMap, name: &'static str, from_handle: FromParamsFn) {
 	if patterns.get(name).is_some() {
 		panic!("The pattern '{}' is already exist.", name);
 	} else {
@@ -428,22 +454,33 @@ pub fn add_pattern(name: &'static str, from_handle: FromParamsFn) {
 	}
 }
 
+// the lib supported patterns
+fn get_patterns() -> &'static PatternMap {
+	PATTERNS.get_or_init(|| {
+		let mut patterns: PatternMap = HashMap::with_capacity(6);
+		add_pattern(&mut patterns, "identity", Box::new(Identity::from_params));
+		add_pattern(&mut patterns, "spaces", Box::new(Spaces::from_params));
+		add_pattern(&mut patterns, "attr_key", Box::new(AttrKey::from_params));
+		add_pattern(&mut patterns, "nth", Box::new(Nth::from_params));
+		add_pattern(&mut patterns, "regexp", Box::new(RegExp::from_params));
+		add_pattern(
+			&mut patterns,
+			"selector",
+			Box::new(NestedSelector::from_params),
+		);
+		patterns
+	})
+}
+
 pub(crate) fn init() {
-	// add lib supported patterns
-	add_pattern("identity", Box::new(Identity::from_params));
-	add_pattern("spaces", Box::new(Spaces::from_params));
-	add_pattern("attr_key", Box::new(AttrKey::from_params));
-	add_pattern("nth", Box::new(Nth::from_params));
-	add_pattern("regexp", Box::new(RegExp::from_params));
-	add_pattern("selector", Box::new(NestedSelector::from_params));
+	get_patterns();
 }
 
 pub fn to_pattern(name: &str, s: &str, p: &str) -> Result<BoxDynPattern, String> {
-	let patterns = PATTERNS.lock().unwrap();
-	if let Some(cb) = patterns.get(name) {
+	if let Some(cb) = get_patterns().get(name) {
 		return cb(s, p);
 	}
-	no_implemented(name);
+	Err(format!("No supported pattern '{}' was found", name))
 }
 
 pub fn exec(queues: &[BoxDynPattern], chars: &[char]) -> (MatchedQueue, usize, usize, bool) {
@@ -539,8 +576,9 @@ mod tests {
 		assert!(pat.matched(&['a']).is_none());
 		assert!(format!("{:?}", pat).contains("Pattern"));
 		assert!(TestPattern::from_params("a", "").is_err());
-		add_pattern("test", Box::new(TestPattern::from_params));
-		add_pattern("test", Box::new(TestPattern::from_params));
+		let mut patterns = Default::default();
+		add_pattern(&mut patterns, "test", Box::new(TestPattern::from_params));
+		add_pattern(&mut patterns, "test", Box::new(TestPattern::from_params));
 	}
 
 	#[test]
@@ -565,6 +603,7 @@ mod tests {
 		// regexp
 		let reg_exp: BoxDynPattern = Box::new(RegExp {
 			context: std::borrow::Cow::from("abc"),
+			..Default::default()
 		});
 		assert!(format!("{:?}", reg_exp).contains("abc"));
 	}
@@ -1,14 +1,60 @@
 use super::pattern::{self, exec, to_pattern, BoxDynPattern, MatchedQueue, Pattern};
+use super::Selector;
 use crate::mesdoc::utils::vec_char_to_clean_str;
 use crate::mesdoc::{
 	constants::PRIORITY_PSEUDO_SELECTOR,
 	interface::{Elements, IElementTrait},
 };
-use lazy_static::lazy_static;
+use std::cell::RefCell;
 use std::fmt;
-use std::sync::{Arc, Mutex};
-lazy_static! {
-	pub static ref RULES: Mutex<Vec<(&'static str, Arc<Rule>)>> = Mutex::new(Vec::with_capacity(20));
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+pub type RuleList = Vec<(&'static str, Arc<Rule>)>;
+// the rules are only written when the lib init or a custom rule is added,
+// each thread keeps a snapshot of the rules and only reads the rules again when the version changed.
+static RULES: RwLock<RuleList> = RwLock::new(Vec::new());
+static RULES_VERSION: AtomicUsize = AtomicUsize::new(0);
+thread_local! {
+	static RULES_SNAPSHOT: RefCell<Rc<(usize, RuleList)>> = RefCell::new(Rc::new((0, Vec::new())));
+}
+
+/// get the snapshot of all rules without lock
+pub(crate) fn get_rules() -> Rc<(usize, RuleList)> {
+	RULES_SNAPSHOT.with(|snapshot| {
+		let version = RULES_VERSION.load(Ordering::Acquire);
+		if snapshot.borrow().0 != version {
+			let rules = RULES.read().unwrap().clone();
+			*snapshot.borrow_mut() = Rc::new((version, rules));
+		}
+		Rc::clone(&snapshot.borrow())
+	})
+}
+
+// the characters in a css identifier
+fn is_name_char(ch: char) -> bool {
+	ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || !ch.is_ascii()
+}
+
+// check if the rule's name is already exist
+pub(crate) fn has_rule(name: &str) -> bool {
+	RULES
+		.read()
+		.unwrap()
+		.iter()
+		.any(|(cur_name, _)| *cur_name == name)
+}
+
+// update the rules and the version
+fn update_rules<F>(handle: F) -> Result<(), String>
+where
+	F: FnOnce(&mut RuleList) -> Result<(), String>,
+{
+	let mut all_rules = RULES.write().unwrap();
+	handle(&mut all_rules)?;
+	RULES_VERSION.fetch_add(1, Ordering::Release);
+	Ok(())
 }
 // matcher handles
 pub type MatchAllHandle = Box<dyn (for<'a, 'r> Fn(&'a Elements<'r>, Option<bool>) -> Elements<'r>)>;
@@ -17,6 +63,8 @@ pub type MatchSpecifiedHandle =
 	Box<dyn for<'a> Fn(&'a dyn IElementTrait, Box<dyn FnMut(&dyn IElementTrait, bool, bool) + 'a>)>;
 // matcher factory
 pub type MatcherFactory = Box<dyn (Fn(MatchedQueue) -> Matcher) + Send + Sync>;
+// matched checker, check the matched data before make a matcher, e.g. a malformed regex
+pub type MatchedChecker = Box<dyn (Fn(&MatchedQueue) -> Result<(), String>) + Send + Sync>;
 
 #[derive(Default)]
 pub struct Matcher {
@@ -25,6 +73,8 @@ pub struct Matcher {
 	pub specified_handle: Option<MatchSpecifiedHandle>,
 	pub priority: u32,
 	pub in_cache: bool,
+	// filter the matched elements set by the index in it, e.g. `:first`, `:eq(1)`
+	pub positional: bool,
 }
 
 impl fmt::Debug for Matcher {
@@ -70,8 +120,8 @@ fn get_char_vec() -> Vec<char> {
 }
 
 // unmatched start or end
-fn panic_unmatched(ch: char, index: usize) -> ! {
-	panic!(
+fn unmatched(ch: char, index: usize) -> String {
+	format!(
 		"Unmatched '{ch}' at index {index},you can escape it using both {ch}{ch}",
 		ch = ch,
 		index = index
@@ -116,6 +166,7 @@ pub struct Rule {
 	pub priority: u32,
 	pub(crate) queues: Vec<Box<dyn Pattern>>,
 	pub handle: MatcherFactory,
+	pub checker: Option<MatchedChecker>,
 }
 
 impl fmt::Debug for Rule {
@@ -126,8 +177,13 @@ impl fmt::Debug for Rule {
 
 // Rule methods
 impl Rule {
-	// translate string to queues
+	// translate string to queues, panic if the content is malformed
 	pub(crate) fn get_queues(content: &str) -> Vec<Box<dyn Pattern>> {
+		Rule::parse_queues(content).unwrap_or_else(|reason| panic!("{}", reason))
+	}
+
+	// translate string to queues, return the reason if the content is malformed
+	pub(crate) fn parse_queues(content: &str) -> Result<Vec<Box<dyn Pattern>>, String> {
 		const ANCHOR_CHAR: char = '\0';
 		const START_CHAR: char = '{';
 		const END_CHAR: char = '}';
@@ -152,13 +208,13 @@ impl Rule {
 				if ch == END_CHAR {
 					is_matched_finish = true;
 				} else {
-					panic!(
+					return Err(format!(
 						"Unexpected end of the pattern '{}' at index {}, expect '{}' but found '{}'",
 						vec_char_to_clean_str(&mut store.names),
 						index - 1,
 						END_CHAR,
 						ch
-					);
+					));
 				}
 			} else if !store.is_in_matched {
 				// when not in matched
@@ -186,8 +242,8 @@ impl Rule {
 						prev_char = ANCHOR_CHAR;
 						continue;
 					} else {
-						// panic no matched
-						panic_unmatched(END_CHAR, index - 2);
+						// no matched
+						return Err(unmatched(END_CHAR, index - 2));
 					}
 				} else {
 					raw_chars.push(ch);
@@ -223,7 +279,7 @@ impl Rule {
 				// in suf_params or names
 				if ch == '}' {
 					if store.hashs_num > 0 {
-						panic!("Uncomplete raw params: ''");
+						return Err(String::from("Uncomplete raw params: ''"));
 					}
 					is_matched_finish = true;
 				} else if ch == '#' {
@@ -245,22 +301,19 @@ impl Rule {
 				}
 			}
 			if is_matched_finish {
-				match store.next() {
-					Ok(queue) => queues.push(queue),
-					Err(reason) => panic!("{}", reason),
-				};
+				queues.push(store.next()?);
 			}
 			prev_char = ch;
 		}
 		// not end
 		if store.is_wait_end || store.is_in_matched {
-			panic!(
+			return Err(format!(
 				"The Mathed type '{}' is not complete",
 				store.names.iter().collect::<String>()
-			);
+			));
 		}
 		if prev_char == START_CHAR || (prev_char == END_CHAR && !is_matched_finish) {
-			panic_unmatched(prev_char, index - 1);
+			return Err(unmatched(prev_char, index - 1));
 		}
 		if !raw_chars.is_empty() {
 			if raw_chars.len() == 1 {
@@ -269,11 +322,16 @@ impl Rule {
 				queues.push(Box::new(raw_chars));
 			}
 		}
-		queues
+		Ok(queues)
 	}
 
 	pub fn exec(&self, chars: &[char]) -> Option<(MatchedQueue, usize, usize)> {
-		Rule::exec_queues(&self.queues, chars)
+		let (matched, len, queue_num) = Rule::exec_queues(&self.queues, chars)?;
+		// the rule can't stop in the middle of a name, e.g. `:e` in `:empty`
+		if is_name_char(chars[len - 1]) && chars.get(len).is_some_and(|&ch| is_name_char(ch)) {
+			return None;
+		}
+		Some((matched, len, queue_num))
 	}
 
 	pub fn exec_queues(
@@ -287,6 +345,14 @@ impl Rule {
 			None
 		}
 	}
+	/// check the matched data, return the reason if it's not allowed
+	pub fn check(&self, data: &MatchedQueue) -> Result<(), String> {
+		match &self.checker {
+			Some(checker) => checker(data),
+			None => Ok(()),
+		}
+	}
+
 	/// make a matcher
 	pub fn make(&self, data: MatchedQueue) -> Matcher {
 		let handle = &self.handle;
@@ -298,11 +364,12 @@ impl Rule {
 
 	/// make a matcher by alias
 	pub fn make_alias(selector: &'static str) -> Matcher {
-		// if parse the selector string into Selector and save to the closure
-		// the mutex rules will trigger a dead lock
-		// so there give up, just lost some performance
+		// the rules are read from the snapshot without lock, so the alias can be parsed once here
+		let selector = Selector::from_str(selector, false).expect("The alias selector must be valid");
 		Matcher {
-			all_handle: Some(Box::new(move |eles: &Elements, _| eles.filter(selector))),
+			all_handle: Some(Box::new(move |eles: &Elements, _| {
+				eles.filter_selector(&selector)
+			})),
 			// priority
 			priority: PRIORITY_PSEUDO_SELECTOR,
 			..Default::default()
@@ -314,6 +381,12 @@ impl Rule {
 		rule.queues = Rule::get_queues(context);
 		rule
 	}
+
+	/// add a rule, return the reason if the context is malformed
+	pub fn try_add(context: &str, mut rule: Rule) -> Result<Self, String> {
+		rule.queues = Rule::parse_queues(context)?;
+		Ok(rule)
+	}
 }
 
 pub struct RuleDefItem(
@@ -338,6 +411,7 @@ impl From<RuleDefItem> for RuleItem {
 				priority: item.2,
 				in_cache: false,
 				handle: item.3,
+				checker: None,
 				queues: Vec::new(),
 			},
 		}
@@ -345,16 +419,39 @@ impl From<RuleDefItem> for RuleItem {
 }
 
 pub fn add_rules(rules: Vec<RuleItem>) {
-	let mut all_rules = RULES.lock().unwrap();
-	for RuleItem {
+	// parse the rules before lock, so a malformed rule can't poison the lock
+	let rules: RuleList = rules
+		.into_iter()
+		.map(
+			|RuleItem {
+			   name,
+			   context,
+			   rule,
+			 }| (name, Arc::new(Rule::add(context, rule))),
+		)
+		.collect();
+	let _ = update_rules(|all_rules| {
+		all_rules.extend(rules);
+		Ok(())
+	});
+}
+
+// insert a rule before all the other rules, the rule's name must be unique
+pub(crate) fn insert_rule(item: RuleItem) -> Result<(), String> {
+	let RuleItem {
 		name,
 		context,
 		rule,
-	} in rules
-	{
-		let cur_rule = Rule::add(context, rule);
-		all_rules.push((name, Arc::new(cur_rule)));
-	}
+	} = item;
+	// parse the rule before lock, so a malformed rule can't poison the lock
+	let cur_rule = Arc::new(Rule::try_add(context, rule)?);
+	update_rules(|all_rules| {
+		if all_rules.iter().any(|(cur_name, _)| *cur_name == name) {
+			return Err(format!("The rule '{}' is already exist.", name));
+		}
+		all_rules.insert(0, (name, cur_rule));
+		Ok(())
+	})
 }
 
 pub(crate) fn init() {
@@ -373,6 +470,7 @@ mod tests {
 			priority: 1,
 			queues: vec![],
 			handle: Box::new(|_| Default::default()),
+			checker: None,
 		};
 		assert!(format!("{:?}", rule).contains("Rule"));
 	}
@@ -0,0 +1,275 @@
+use super::{Combinator, Matched, MatchedQueue, Selector};
+use crate::mesdoc::constants::{
+	NAME_SELECTOR_ATTR, NAME_SELECTOR_CLASS, NAME_SELECTOR_ID, NAME_SELECTOR_NAME,
+	NAME_SELECTOR_NS_ATTR, NAME_SELECTOR_NS_NAME,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Add;
+
+/// The css specificity `(a, b, c)`: the count of the id selectors,
+/// the class, attribute and pseudo-class selectors, and the type selectors.
+///
+/// The specificities can be compared directly, `:is()`, `:not()` and `:has()` take the most specific selector in their arguments,
+/// `:where()` is always `(0, 0, 0)`, `:nth-child(An+B of S)` adds the most specific selector in `S` to a pseudo-class.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity(pub usize, pub usize, pub usize);
+
+impl Add for Specificity {
+	type Output = Specificity;
+	fn add(self, other: Specificity) -> Specificity {
+		Specificity(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+	}
+}
+
+impl fmt::Display for Specificity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "({}, {}, {})", self.0, self.1, self.2)
+	}
+}
+
+// the raw length of the matched pattern, include the escape characters
+fn get_raw_len(matched: &Matched) -> usize {
+	matched.chars.len() + matched.ignore_chars.unwrap_or(0)
+}
+
+// collapse the whitespaces into one space
+fn collapse_whitespace(context: &str) -> String {
+	let mut result = String::with_capacity(context.len());
+	for ch in context.chars() {
+		if ch.is_whitespace() {
+			if !result.ends_with(' ') {
+				result.push(' ');
+			}
+		} else {
+			result.push(ch);
+		}
+	}
+	result
+}
+
+// quote the value with double quotes, keep the original quote if the value has double quotes or escapes
+fn quote_value(value: &str, quote: Option<char>) -> String {
+	if !value.contains(['"', '\\']) {
+		return format!("\"{}\"", value);
+	}
+	match quote {
+		Some(quote) => format!("{}{}{}", quote, value, quote),
+		None => String::from(value),
+	}
+}
+
+// normalize the value part of the attribute selector, e.g. `= 'a'  I` to `="a" i`
+fn normalize_attr_value(data: &HashMap<String, String>) -> String {
+	if let Some(source) = data.get("6") {
+		let flags = data.get("7").map(|s| s.as_str()).unwrap_or("");
+		return format!("~/{}/{}", source, flags);
+	}
+	let value = [("2", Some('\'')), ("4", Some('"')), ("3", None)]
+		.iter()
+		.find_map(|(key, quote)| data.get(*key).map(|value| quote_value(value, *quote)));
+	match value {
+		Some(value) => {
+			let mode = data.get("1").map(|s| s.as_str()).unwrap_or("");
+			let mut result = format!("{}={}", mode, value);
+			if let Some(flag) = data.get("5") {
+				result.push(' ');
+				result.push_str(&flag.to_ascii_lowercase());
+			}
+			result
+		}
+		None => String::new(),
+	}
+}
+
+// normalize the regexp parameter, the single quoted string is changed to double quoted,
+// the whitespaces are collapsed if the parameter has no quotes and regex
+fn normalize_regexp(context: &str) -> String {
+	if let Some(value) = context
+		.strip_prefix('\'')
+		.and_then(|value| value.strip_suffix('\''))
+	{
+		if !value.contains(['\'', '"', '\\']) {
+			return format!("\"{}\"", value);
+		}
+	}
+	if context.contains(['\'', '"', '/', '\\']) {
+		return String::from(context);
+	}
+	collapse_whitespace(context)
+}
+
+// normalize the nested selector list, keep the raw context if it can't be parsed
+fn normalize_nested(context: &str) -> String {
+	let context = context.trim();
+	Selector::with_sources(context)
+		.map(|selector| selector.to_string())
+		.unwrap_or_else(|_| String::from(context))
+}
+
+// a part of the compound selector's context
+#[derive(Debug)]
+enum SourcePart {
+	Text(String),
+	// the nested selector list, normalized when it's printed
+	Nested(String),
+}
+
+// a compound selector in the selector source, e.g. `a.item:not(.ad)`
+#[derive(Debug)]
+pub(crate) struct SegmentSource {
+	// the combinator before the compound selector
+	pub comb: Combinator,
+	parts: Vec<SourcePart>,
+	// the specificity of the rules, the nested selectors are not included
+	specificity: Specificity,
+	// the nested selector lists that count in the specificity, e.g. `.a` of `:not(.a)`
+	nested: Vec<String>,
+}
+
+impl SegmentSource {
+	pub fn new(comb: Combinator) -> Self {
+		SegmentSource {
+			comb,
+			parts: Vec::with_capacity(1),
+			specificity: Specificity::default(),
+			nested: Vec::new(),
+		}
+	}
+	// add a rule of the compound selector
+	pub fn add_rule(&mut self, name: &str, matched: &MatchedQueue, context: &[char]) {
+		self.add_parts(name, matched, context);
+		let nested = matched
+			.iter()
+			.find(|item| item.name == "selector")
+			.map(|item| item.chars.iter().collect::<String>());
+		let (specificity, count_nested) = match name {
+			NAME_SELECTOR_ID => (Specificity(1, 0, 0), false),
+			NAME_SELECTOR_CLASS | NAME_SELECTOR_ATTR | NAME_SELECTOR_NS_ATTR => {
+				(Specificity(0, 1, 0), false)
+			}
+			NAME_SELECTOR_NAME => (Specificity(0, 0, 1), false),
+			// `ns|*` is an universal selector
+			NAME_SELECTOR_NS_NAME if matched[0].data.contains_key("3") => (Specificity(0, 0, 1), false),
+			":is" | ":not" | ":has" => (Specificity::default(), true),
+			":where" => (Specificity::default(), false),
+			_ if name.starts_with(":nth-child(") || name.starts_with(":nth-last-child(") => {
+				(Specificity(0, 1, 0), true)
+			}
+			_ if name.starts_with(':') => (Specificity(0, 1, 0), false),
+			_ => (Specificity::default(), false),
+		};
+		self.specificity = self.specificity + specificity;
+		if count_nested {
+			self.nested.extend(nested);
+		}
+	}
+	// append the text to the last text part
+	fn push_text(&mut self, text: &str, space_before: bool) {
+		if let Some(SourcePart::Text(cur)) = self.parts.last_mut() {
+			// the spaces after '(' '[' and before ')' ']' are removed
+			let is_open = cur.ends_with(['(', '[']);
+			if text.starts_with([')', ']']) {
+				cur.truncate(cur.trim_end().len());
+			} else if space_before && !is_open {
+				cur.push(' ');
+			}
+			cur.push_str(if is_open { text.trim_start() } else { text });
+		} else {
+			self.parts.push(SourcePart::Text(String::from(text)));
+		}
+	}
+	// split the rule's context into normalized parts by the matched patterns
+	fn add_parts(&mut self, name: &str, matched: &MatchedQueue, context: &[char]) {
+		let total_len: usize = matched.iter().map(get_raw_len).sum();
+		if total_len != context.len() {
+			let context = context.iter().collect::<String>();
+			self.push_text(collapse_whitespace(&context).trim(), false);
+			return;
+		}
+		let value_index = if matches!(name, NAME_SELECTOR_ATTR | NAME_SELECTOR_NS_ATTR) {
+			matched.iter().rposition(|item| item.name == "regexp")
+		} else {
+			None
+		};
+		let mut offset = 0;
+		let mut space_before = false;
+		for (index, item) in matched.iter().enumerate() {
+			let len = get_raw_len(item);
+			let raw = context[offset..offset + len].iter().collect::<String>();
+			offset += len;
+			let text = match item.name {
+				"spaces" => {
+					space_before = space_before || len > 0;
+					continue;
+				}
+				"selector" => {
+					if space_before {
+						self.push_text("", true);
+					}
+					self.parts.push(SourcePart::Nested(raw));
+					space_before = false;
+					continue;
+				}
+				"nth" => raw.split_whitespace().collect::<String>(),
+				"regexp" if Some(index) == value_index => {
+					space_before = false;
+					normalize_attr_value(&item.data)
+				}
+				"regexp" => normalize_regexp(&raw),
+				_ => raw,
+			};
+			if !text.is_empty() {
+				self.push_text(&text, space_before);
+				space_before = false;
+			}
+		}
+	}
+	// the specificity with the nested selectors
+	pub fn get_specificity(&self) -> Specificity {
+		self.nested.iter().fold(self.specificity, |total, nested| {
+			let max = Selector::with_sources(nested)
+				.map(|selector| selector.specificity().into_iter().max().unwrap_or_default())
+				.unwrap_or_default();
+			total + max
+		})
+	}
+	// the normalized context of the compound selector
+	pub fn to_context(&self) -> String {
+		self
+			.parts
+			.iter()
+			.map(|part| match part {
+				SourcePart::Text(text) => text.clone(),
+				SourcePart::Nested(context) => normalize_nested(context),
+			})
+			.collect()
+	}
+}
+
+// the combinator in the selector context
+fn get_comb_context(comb: &Combinator) -> &'static str {
+	match comb {
+		Combinator::Children => ">",
+		Combinator::Next => "+",
+		Combinator::NextAll => "~",
+		_ => "",
+	}
+}
+
+// join the compound selectors with the combinators, the first combinator is kept if it's relative
+pub(crate) fn join_segments(segments: &[SegmentSource], keep_head: bool) -> String {
+	let mut context = String::new();
+	for (index, segment) in segments.iter().enumerate() {
+		let comb = get_comb_context(&segment.comb);
+		if index > 0 {
+			context.push(' ');
+		}
+		if (index > 0 || keep_head) && !comb.is_empty() {
+			context.push_str(comb);
+			context.push(' ');
+		}
+		context.push_str(&segment.to_context());
+	}
+	context
+}
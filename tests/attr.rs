@@ -1,5 +1,5 @@
 use std::result::Result as StdResult;
-use visdom::types::BoxDynError;
+use visdom::types::{BoxDynError, DataValue};
 use visdom::Vis;
 type Result = StdResult<(), BoxDynError>;
 
@@ -113,3 +113,136 @@ fn test_class_attr() -> Result {
 	assert!(div.has_class("second"));
 	Ok(())
 }
+
+#[test]
+fn test_data_attr() -> Result {
+	let html = r#"<div id="item" data-id="10" data-ratio="0.5" data-big-num="1e3" data-zero-pad="007" data-flag="false" data-empty="null" data-props='{"a": 1}' data-Upper-Case="x" data-bool></div>"#;
+	let root = Vis::load(html)?;
+	let mut item = root.find("#item");
+	// parse the values
+	assert_eq!(item.data("id"), Some(DataValue::Number(10.0)));
+	assert_eq!(item.data("ratio"), Some(DataValue::Number(0.5)));
+	assert_eq!(item.data("bigNum").unwrap().as_text().unwrap(), "1e3");
+	assert_eq!(item.data("zeroPad").unwrap().as_number(), None);
+	assert_eq!(item.data("flag").unwrap().as_bool(), Some(false));
+	assert!(item.data("empty").unwrap().is_null());
+	assert_eq!(item.data("upperCase").unwrap().as_text().unwrap(), "x");
+	assert_eq!(item.data("bool"), Some(DataValue::Text(String::new())));
+	assert!(item.data("none").is_none());
+	// dataset
+	let dataset = item.dataset();
+	assert_eq!(
+		dataset
+			.keys()
+			.map(|key| key.as_str())
+			.collect::<Vec<&str>>(),
+		vec![
+			"bigNum",
+			"bool",
+			"empty",
+			"flag",
+			"id",
+			"props",
+			"ratio",
+			"upperCase",
+			"zeroPad"
+		]
+	);
+	// write back
+	item
+		.set_data("id", 11)
+		.set_data("newKey", "a \"quoted\" & 'text'");
+	assert_eq!(item.data("id"), Some(DataValue::Number(11.0)));
+	assert!(item.has_attr("data-new-key"));
+	assert_eq!(
+		item.data("new-key").unwrap().as_text().unwrap(),
+		"a \"quoted\" & 'text'"
+	);
+	item.set_data("text", "&amp;");
+	assert_eq!(item.data("text").unwrap().as_text().unwrap(), "&amp;");
+	item.remove_data("newKey");
+	assert!(!item.has_attr("data-new-key"));
+	// the numbers that can't be written back as the same text in javascript
+	for value in [
+		"-0",
+		"0.0",
+		"+1",
+		".5",
+		"1e21",
+		"1000000000000000000000",
+		"0.0000001",
+		"1e+021",
+		"1E+21",
+		"1e-07",
+		"1e+20",
+		"NaN",
+		"Infinity",
+		"-inf",
+		" 1",
+	] {
+		assert_eq!(
+			DataValue::parse(value),
+			DataValue::Text(String::from(value))
+		);
+	}
+	for (value, num) in [
+		("0", 0.0),
+		("-1.5", -1.5),
+		("0.000001", 0.000001),
+		("100000000000000000000", 1e20),
+		("1e+21", 1e21),
+		("-1.5e+25", -1.5e25),
+		("1e-7", 1e-7),
+		("1.5e-7", 1.5e-7),
+	] {
+		assert_eq!(DataValue::parse(value), DataValue::Number(num));
+	}
+	// `-0` is written as `0`, the non-finite numbers are ignored
+	item.set_data("zero", -0.0);
+	assert_eq!(item.attr("data-zero").unwrap().to_string(), "0");
+	assert_eq!(item.data("zero"), Some(DataValue::Number(0.0)));
+	// the numbers out of the range are written with exponent
+	item
+		.set_data("big", 1e21)
+		.set_data("small", 1e-7)
+		.set_data("max", f64::MAX)
+		.set_data("min", 5e-324);
+	assert_eq!(item.attr("data-big").unwrap().to_string(), "1e+21");
+	assert_eq!(item.attr("data-small").unwrap().to_string(), "1e-7");
+	assert_eq!(item.data("big"), Some(DataValue::Number(1e21)));
+	assert_eq!(item.data("small"), Some(DataValue::Number(1e-7)));
+	assert_eq!(item.data("max"), Some(DataValue::Number(f64::MAX)));
+	assert_eq!(item.data("min"), Some(DataValue::Number(5e-324)));
+	item
+		.set_data("nan", f64::NAN)
+		.set_data("inf", f64::INFINITY);
+	assert!(!item.has_attr("data-nan"));
+	assert!(!item.has_attr("data-inf"));
+	item.set_data("id", f64::NEG_INFINITY);
+	assert_eq!(item.data("id"), Some(DataValue::Number(11.0)));
+	// empty elements
+	assert!(root.find("p").data("id").is_none());
+	assert!(root.find("p").dataset().is_empty());
+	Ok(())
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_data_json() -> Result {
+	let html = r#"<div id="item" data-props="{&quot;name&quot;: &quot;apple&quot;, &quot;tags&quot;: [1, 2]}" data-list='[1, 2'></div>"#;
+	let root = Vis::load(html)?;
+	let mut item = root.find("#item");
+	let props = item.data("props").unwrap();
+	let json = props.as_json().unwrap();
+	assert_eq!(json["name"], "apple");
+	assert_eq!(json["tags"][1], 2);
+	// not a valid json
+	assert_eq!(
+		item.data("list"),
+		Some(DataValue::Text(String::from("[1, 2")))
+	);
+	// write back the json
+	item.set_data("copy", serde_json::json!({"a": "b\"c"}));
+	assert_eq!(item.data("copy").unwrap().as_json().unwrap()["a"], "b\"c");
+	Ok(())
+}
@@ -1,3 +1,4 @@
+use std::{cell::RefCell, rc::Rc};
 use visdom::{types::BoxDynError, Vis};
 
 #[test]
@@ -73,3 +74,43 @@ fn test_wrong_selector_catch() {
 	// no pseudo selector ":all-child"
 	let _ = root.find("a:all-childs");
 }
+
+#[test]
+fn test_wrong_regex_selector_catch() {
+	let html = r#"
+  <!doctype html>
+  <html>
+    <head></head>
+    <a href="/product/1">1 item</a>
+  </html>
+  "#;
+	let errors = Rc::new(RefCell::new(Vec::new()));
+	let catched = Rc::clone(&errors);
+	let root = Vis::load_catch(
+		html,
+		Box::new(move |e: BoxDynError| {
+			catched.borrow_mut().push(e.to_string());
+		}),
+	);
+	// unclosed group
+	assert!(root.find("a[href~/(\\d+/]").is_empty());
+	// unsupported flag
+	assert!(root.find("a:matches(/item/g)").is_empty());
+	// nested
+	assert!(root.find("a:not(:matches(/[a-/))").is_empty());
+	let errors = errors.borrow();
+	assert_eq!(errors.len(), 3);
+	assert!(errors[0].contains("Wrong regex '/(\\d+/'"));
+	assert!(errors[1].contains("Unsupported flag 'g'"));
+	assert!(errors[2].contains("Wrong regex '/[a-/'"));
+}
+
+#[test]
+fn test_unclosed_nested_selector() {
+	assert!(Vis::compile("a:not(.item").is_err());
+	assert!(Vis::compile("a:has(b").is_err());
+	assert!(Vis::compile("li:nth-child(2n of .item").is_err());
+	// the nested pseudo selectors followed by other selectors
+	assert!(Vis::compile(":is(:not(.a) .b)").is_ok());
+	assert!(Vis::compile("div:has(:not(.a) > .b)").is_ok());
+}
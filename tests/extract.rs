@@ -0,0 +1,155 @@
+use std::result::Result as StdResult;
+use visdom::extract::{Field, Schema, Source, Value};
+use visdom::types::BoxDynError;
+use visdom::Vis;
+type Result = StdResult<(), BoxDynError>;
+
+const HTML: &str = r##"
+  <div id="list">
+    <div class="card" data-id="1">
+      <h3 class="title">
+        Apple
+      </h3>
+      <a href="/product/1">detail</a>
+      <span class="price">Price: $1,200.50</span>
+      <input type="text" value="10" />
+      <p class="desc"><b>fresh</b> fruit</p>
+    </div>
+    <div class="card" data-id="2">
+      <h3 class="title">Banana</h3>
+      <a>detail</a>
+      <span class="price">sold out</span>
+    </div>
+  </div>
+"##;
+
+#[test]
+fn test_extract_fields() -> Result {
+	let root = Vis::load(HTML)?;
+	let schema = Schema::new(".card")?
+		.field("id", Field::attr("", "data-id")?.number())
+		.field("title", Field::text(".title")?.trim())
+		.field("link", Field::new("a", "attr:href")?)
+		.field(
+			"price",
+			Field::text(".price")?.capture(r"\$([\d,.]+)")?.number(),
+		)
+		.field("count", Field::val("input")?.number())
+		.field("desc", Field::html(".desc")?)
+		.field("outer", Field::new(".desc b", "outer_html")?)
+		.field("missing", Field::text(".missing")?);
+	let records = schema.extract(&root);
+	assert_eq!(records.len(), 2);
+	let first = &records[0];
+	assert_eq!(first["id"], Value::Number(1.0));
+	assert_eq!(first["title"].as_text().as_deref(), Some("Apple"));
+	assert_eq!(first["link"], Value::Text(String::from("/product/1")));
+	assert_eq!(first["price"].as_number(), Some(1200.5));
+	assert_eq!(first["count"], Value::Number(10.0));
+	assert_eq!(
+		first["desc"],
+		Value::Text(String::from("<b>fresh</b> fruit"))
+	);
+	assert_eq!(first["outer"], Value::Text(String::from("<b>fresh</b>")));
+	assert!(first["missing"].is_null());
+	let second = &records[1];
+	assert_eq!(second["id"], Value::Number(2.0));
+	assert_eq!(second["title"], Value::Text(String::from("Banana")));
+	// no attribute
+	assert!(second["link"].is_null());
+	// capture failed
+	assert!(second["price"].is_null());
+	assert!(second["count"].is_null());
+	// the field with a same name is replaced
+	let schema = Schema::new(".card")?
+		.field("title", Field::text(".title")?)
+		.field("title", Field::text(".title")?.trim().capture("^B.*")?);
+	let records = schema.extract(&root);
+	assert!(records[0]["title"].is_null());
+	assert_eq!(records[1]["title"], Value::Text(String::from("Banana")));
+	// the numbers must be finite
+	let root = Vis::load(
+		r#"<ul><li>NaN</li><li>inf</li><li>-Infinity</li><li>1e400</li><li> -1,000.5 </li></ul>"#,
+	)?;
+	let schema = Schema::new("li")?.field("num", Field::text("")?.number());
+	let nums = schema
+		.extract(&root)
+		.into_iter()
+		.map(|record| record["num"].clone())
+		.collect::<Vec<Value>>();
+	assert_eq!(
+		nums,
+		vec![
+			Value::Null,
+			Value::Null,
+			Value::Null,
+			Value::Null,
+			Value::Number(-1000.5)
+		]
+	);
+	Ok(())
+}
+
+#[test]
+fn test_extract_without_scope() -> Result {
+	let root = Vis::load(HTML)?;
+	let schema = Schema::new("")?
+		.field(
+			"total",
+			Field::attr("#list .card:last-child", "data-id")?.number(),
+		)
+		.field("first_title", Field::text(".title")?.trim());
+	let records = schema.extract(&root);
+	assert_eq!(records.len(), 1);
+	assert_eq!(records[0]["total"], Value::Number(2.0));
+	assert_eq!(
+		records[0]["first_title"],
+		Value::Text(String::from("Apple"))
+	);
+	// empty elements
+	assert!(schema.extract(&root.find(".none")).is_empty());
+	Ok(())
+}
+
+#[test]
+fn test_extract_wrong_schema() {
+	assert_eq!(
+		"attr:href".parse::<Source>().ok(),
+		Some(Source::Attr(String::from("href")))
+	);
+	assert!("attr:".parse::<Source>().is_err());
+	assert!("attr: ".parse::<Source>().is_err());
+	// the empty attribute name is rejected the same way
+	assert!(Field::attr("a", "").is_err());
+	assert!(Field::attr("a", " ").is_err());
+	assert!(Field::with_source("a", Source::Attr(String::new())).is_err());
+	assert_eq!(
+		Field::attr("a", "").err().map(|err| err.to_string()),
+		"attr:".parse::<Source>().err().map(|err| err.to_string())
+	);
+	assert!("inner".parse::<Source>().is_err());
+	assert!(Field::new("a", "link").is_err());
+	assert!(Field::text("a[").is_err());
+	assert!(Field::text("a")
+		.and_then(|field| field.capture("("))
+		.is_err());
+	assert!(Schema::new(".card >").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_extract_json() -> Result {
+	let root = Vis::load(HTML)?;
+	let schema = Schema::new(".card")?
+		.field("title", Field::text(".title")?.trim())
+		.field(
+			"price",
+			Field::text(".price")?.capture(r"\$([\d,.]+)")?.number(),
+		);
+	let records = schema.extract_json(&root);
+	assert_eq!(
+		serde_json::Value::Array(records).to_string(),
+		r#"[{"price":1200.5,"title":"Apple"},{"price":null,"title":"Banana"}]"#
+	);
+	Ok(())
+}
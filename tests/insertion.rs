@@ -1,6 +1,6 @@
 #![cfg(feature = "insertion")]
 use std::result::Result as StdResult;
-use visdom::types::BoxDynError;
+use visdom::types::{BoxDynError, InsertPosition};
 use visdom::Vis;
 type Result = StdResult<(), BoxDynError>;
 
@@ -210,3 +210,186 @@ fn test_append_wrong_parent() {
 	let mut parent = root.find("#main");
 	child.append(&mut parent);
 }
+
+#[test]
+fn test_wrap() -> Result {
+	let html =
+		r#"<div class="content"><table id="t1"></table><span>-</span><table id="t2"></table></div>"#;
+	let root = Vis::load(html)?;
+	let mut tables = root.find("table");
+	tables.wrap(&Vis::load(r#"<div class="scroll"></div>"#)?);
+	assert_eq!(
+		root.find(".content").html(),
+		r#"<div class="scroll"><table id="t1"></table></div><span>-</span><div class="scroll"><table id="t2"></table></div>"#
+	);
+	// nested wrapper, the element is moved into the innermost element
+	let root = Vis::load(r#"<ul><li>a</li></ul>"#)?;
+	root.find("ul").wrap(&Vis::load(
+		r#"<nav><div class="outer"><div class="inner"></div><p></p></div></nav>"#,
+	)?);
+	assert_eq!(root.find("nav > .outer > .inner > ul > li").text(), "a");
+	assert_eq!(root.find("nav p").length(), 1);
+	// the element is not allowed in the wrapper, keep the element
+	let root = Vis::load(r#"<div id="main"><span>a</span></div>"#)?;
+	root.find("span").wrap(&Vis::load("<title></title>")?);
+	assert_eq!(root.find("#main").html(), "<span>a</span>");
+	assert!(root.find("title").is_empty());
+	Ok(())
+}
+
+#[test]
+fn test_wrap_all() -> Result {
+	let html = r#"<div class="content"><h3>title</h3><p>a</p><span>-</span><p>b</p></div>"#;
+	let root = Vis::load(html)?;
+	root.find("p").wrap_all(&Vis::load(
+		r#"<section><div class="body"></div></section>"#,
+	)?);
+	assert_eq!(
+		root.find(".content").html(),
+		r#"<h3>title</h3><section><div class="body"><p>a</p><p>b</p></div></section><span>-</span>"#
+	);
+	// the elements not allowed in the wrapper keep in their place
+	let root = Vis::load(r#"<div id="main"><span>a</span><b>b</b></div>"#)?;
+	root
+		.find("span, b")
+		.wrap_all(&Vis::load("<title></title>")?);
+	assert_eq!(root.find("#main").html(), "<span>a</span><b>b</b>");
+	assert!(root.find("title").is_empty());
+	Ok(())
+}
+
+#[test]
+fn test_wrap_inner() -> Result {
+	let html = r#"<div class="text">plain <b>bold</b><!--comment--></div><div class="text"></div>"#;
+	let root = Vis::load(html)?;
+	let mut texts = root.find(".text");
+	texts.wrap_inner(&Vis::load("<p></p>")?);
+	assert_eq!(texts.eq(0).html(), "<p>plain <b>bold</b><!--comment--></p>");
+	assert_eq!(texts.eq(1).html(), "<p></p>");
+	// the child nodes are not allowed in the wrapper
+	let root = Vis::load(r#"<div class="text"><li>a</li></div>"#)?;
+	root
+		.find(".text")
+		.wrap_inner(&Vis::load("<textarea></textarea>")?);
+	assert_eq!(root.find(".text").html(), "<li>a</li>");
+	Ok(())
+}
+
+#[test]
+fn test_unwrap() -> Result {
+	let html = r#"<body><p><font color="red">red <span>a</span></font><font><span>b</span> text</font></p></body>"#;
+	let root = Vis::load(html)?;
+	root.find("font > span").unwrap();
+	assert_eq!(
+		root.find("p").html(),
+		"red <span>a</span><span>b</span> text"
+	);
+	// the body and html elements are kept
+	root.find("p").unwrap();
+	assert_eq!(root.find("body > p").length(), 1);
+	Ok(())
+}
+
+#[test]
+fn test_insert_data_node() -> Result {
+	let html = r#"<html><head><title>document</title></head><body><svg></svg></body></html>"#;
+	let root = Vis::load(html)?;
+	// comment
+	let mut head = root.find("head");
+	head.prepend(&mut Vis::comment(" MIT License ")?);
+	assert_eq!(head.html(), "<!-- MIT License --><title>document</title>");
+	// the cdata can only insert into foreign elements
+	let mut body = root.find("body");
+	body.append(&mut Vis::cdata("x")?);
+	assert_eq!(body.html(), "<svg></svg>");
+	let mut svg = root.find("svg");
+	svg.append(&mut Vis::cdata("a < b")?);
+	assert_eq!(svg.html(), "<![CDATA[a < b]]>");
+	Ok(())
+}
+
+#[test]
+fn test_create_nodes() -> Result {
+	let html = r#"<html><head></head><body><div id="content"></div></body></html>"#;
+	let root = Vis::load(html)?;
+	let document = root.document().unwrap();
+	// build a list
+	let mut list = document.create_element("ul")?;
+	list.set_attribute("class", Some("list"));
+	for name in ["a & b", "<c>"] {
+		let mut item = document.create_element("li")?;
+		item.set_attribute("data-name", Some(name));
+		let text = document.create_text_node(name)?;
+		item.insert_adjacent(&InsertPosition::BeforeEnd, &text);
+		list.insert_adjacent(&InsertPosition::BeforeEnd, &item);
+	}
+	let comment = document.create_comment(" generated ")?;
+	list.insert_adjacent(&InsertPosition::AfterBegin, &comment);
+	// void element
+	let mut img = document.create_element("img")?;
+	img.set_attribute("src", Some("logo.png"));
+	assert_eq!(img.outer_html(), r#"<img src="logo.png">"#);
+	// the created nodes can be queried before inserted
+	let list = Vis::dom(&list);
+	assert_eq!(list.find("li").length(), 2);
+	assert_eq!(list.find("li").text(), "a & b<c>");
+	// insert into the document
+	let mut content = root.find("#content");
+	content.append(&mut list.clone());
+	content.append(&mut Vis::dom(&img));
+	assert_eq!(
+		content.html(),
+		r#"<ul class="list"><!-- generated --><li data-name="a & b">a &amp; b</li><li data-name="<c>">&lt;c&gt;</li></ul><img src="logo.png">"#
+	);
+	assert_eq!(
+		root.find("#content > ul.list > li:last-child").text(),
+		"<c>"
+	);
+	assert_eq!(root.find("#content img").length(), 1);
+	assert!(list.get(0).unwrap().owner_document().is_some());
+	// invalid nodes
+	assert!(document.create_element("").is_err());
+	assert!(document.create_element("1a").is_err());
+	assert!(document.create_element("a b").is_err());
+	assert!(document.create_element("a\u{1}").is_err());
+	assert!(document.create_element("a\u{7f}").is_err());
+	assert!(document.create_comment("-->").is_err());
+	assert!(document.create_element("my-element").is_ok());
+	assert!(document.create_element("svg:rect").is_ok());
+	// the attribute and text are encoded
+	let mut link = document.create_element("a")?;
+	link.set_attribute("title", Some(r#"say "hi""#));
+	link.set_text("<b>&</b>");
+	assert_eq!(
+		link.outer_html(),
+		r#"<a title='say "hi"'>&lt;b&gt;&amp;&lt;/b&gt;</a>"#
+	);
+	assert_eq!(link.text(), "<b>&</b>");
+	Ok(())
+}
+
+#[test]
+fn test_created_nodes_element_methods() -> Result {
+	let root = Vis::load(r#"<div id="content"></div>"#)?;
+	let document = root.document().unwrap();
+	let text = document.create_text_node("a < b")?;
+	let comment = document.create_comment(" note ")?;
+	for mut node in [text, comment] {
+		// the element methods don't panic on the text and comment nodes
+		assert!(node.get_attribute("id").is_none());
+		assert!(node.get_attributes().is_empty());
+		assert!(!node.has_attribute("id"));
+		node.set_attribute("id", Some("a"));
+		node.remove_attribute("id");
+		assert!(node.get_attribute("id").is_none());
+		assert!(node.children().is_empty());
+		assert_eq!(node.child_nodes_length(), 0);
+		assert!(node.parent().is_none());
+		assert!(node.siblings().is_empty());
+		let _ = node.value();
+		let _ = node.inner_html();
+		let _ = node.outer_html();
+		let _ = node.text_contents();
+	}
+	Ok(())
+}
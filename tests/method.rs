@@ -1,5 +1,5 @@
 use std::result::Result as StdResult;
-use visdom::types::{BoxDynError, Combinator, Elements, IAttrValue};
+use visdom::types::{BoxDynError, Combinator, Elements, IAttrValue, INodeType};
 use visdom::Vis;
 type Result = StdResult<(), BoxDynError>;
 
@@ -396,6 +396,48 @@ fn test_method_children() -> Result {
 	Ok(())
 }
 
+#[test]
+fn test_method_contents() -> Result {
+	let html = r#"<!DOCTYPE html><div id="a">a<!--c1--><b>b</b></div><svg><text>i<![CDATA[data]]><tspan>t</tspan></text></svg>"#;
+	let root = Vis::load(html)?;
+	// the root contents include the doctype
+	let root_contents = root.contents();
+	assert_eq!(root_contents.length(), 3);
+	assert_eq!(
+		root_contents.filter_type(INodeType::HTMLDOCTYPE).length(),
+		1
+	);
+	// document order
+	let contents = root.find("#a, svg text").contents();
+	let types = contents
+		.get_ref()
+		.iter()
+		.map(|node| node.node_type())
+		.collect::<Vec<INodeType>>();
+	assert_eq!(
+		types,
+		vec![
+			INodeType::Text,
+			INodeType::Comment,
+			INodeType::Element,
+			INodeType::Text,
+			INodeType::XMLCDATA,
+			INodeType::Element
+		]
+	);
+	// filter
+	assert_eq!(contents.elements().length(), 2);
+	assert_eq!(contents.elements().text(), "bt");
+	assert_eq!(contents.filter_type(INodeType::Comment).length(), 1);
+	assert_eq!(contents.filter_type(INodeType::XMLCDATA).length(), 1);
+	let not_elements = contents.filter_by(|_, node| !node.node_type().is_element());
+	assert_eq!(not_elements.length(), 4);
+	// empty
+	assert!(root.find("b").contents().elements().is_empty());
+	assert!(root.find("p").contents().is_empty());
+	Ok(())
+}
+
 #[test]
 fn test_method_parent() -> Result {
 	let root = Vis::load(HTML)?;
@@ -707,8 +749,8 @@ fn test_method_closest() -> Result {
 	)?;
 	let abc = root.find("a,b,c");
 	assert_eq!(abc.length(), 4);
-	// wrong selector :first
-	assert_eq!(abc.closest(":first").length(), 0);
+	// wrong selector :unknown
+	assert_eq!(abc.closest(":unknown").length(), 0);
 	// empty selector, always return empty elements
 	assert_eq!(abc.closest("").length(), 0);
 	let closest = abc.closest(".closest");
@@ -1,5 +1,5 @@
 use std::result::Result as StdResult;
-use visdom::types::BoxDynError;
+use visdom::types::{BoxDynError, Combinator, CompiledSelector, Specificity};
 use visdom::Vis;
 type Result = StdResult<(), BoxDynError>;
 
@@ -62,6 +62,78 @@ fn test_attribute_selector() -> Result {
 	Ok(())
 }
 
+#[test]
+fn test_attribute_selector_case_flags() -> Result {
+	let html = r##"
+  <nav id="lang">
+    <a href="#" lang="EN">EN</a>
+    <a href="#" lang="en-UK">en-UK</a>
+    <a href="#" lang="English">English</a>
+    <a href="#" lang="uk En">uk-En</a>
+  </nav>
+  "##;
+	let root = Vis::load(html)?;
+	let links = root.find("#lang").children("");
+	// case-sensitive by default
+	assert_eq!(links.filter("[lang='en']").length(), 0);
+	assert_eq!(links.filter("[lang^='en']").length(), 1);
+	// `i` flag
+	assert_eq!(links.filter("[lang='en' i]").length(), 1);
+	assert_eq!(links.filter("[lang=en i]").length(), 1);
+	assert_eq!(links.filter("[lang=\"en\" I]").length(), 1);
+	assert_eq!(links.filter("[lang^='en' i]").length(), 3);
+	assert_eq!(links.filter("[lang$='EN' i]").length(), 2);
+	assert_eq!(links.filter("[lang*='N' i]").length(), 4);
+	assert_eq!(links.filter("[lang|='EN' i]").length(), 2);
+	assert_eq!(links.filter("[lang~='en' i]").length(), 2);
+	assert_eq!(links.filter("[lang!='en' i]").length(), 3);
+	assert_eq!(links.filter("[ lang = 'en' i ]").length(), 1);
+	// `s` flag
+	assert_eq!(links.filter("[lang='EN' s]").length(), 1);
+	assert_eq!(links.filter("[lang='en' s]").length(), 0);
+	assert_eq!(links.filter("[lang^='En' S]").length(), 1);
+	// the flag can follow the quoted value without spaces
+	assert_eq!(links.filter("[lang=\"en\"i]").length(), 1);
+	assert_eq!(links.filter("[lang^='En's]").length(), 1);
+	assert_eq!(
+		Vis::compile("[lang=\"en\"i]")?.to_string(),
+		"[lang=\"en\" i]"
+	);
+	// the unquoted value takes all the name characters
+	assert_eq!(links.filter("[lang=eni]").length(), 0);
+	// the flag can't be used without a value
+	assert!(root.find("[lang i]").is_empty());
+	Ok(())
+}
+
+#[test]
+fn test_regex_selector() -> Result {
+	let html = r##"
+  <ul id="list">
+    <li><a href="/product/123">10 items</a></li>
+    <li><a href="/product/abc">1 item</a></li>
+    <li><a href="/Product/456">no Items</a></li>
+    <li><a href="https://example.com/product/7">1 ITEM</a></li>
+    <li><a href>0 item</a></li>
+  </ul>
+  "##;
+	let root = Vis::load(html)?;
+	let links = root.find("#list a");
+	// attribute
+	assert_eq!(links.filter("[href~/\\/product\\/\\d+/]").length(), 2);
+	assert_eq!(links.filter("[href~/^\\/product\\/\\d+$/]").length(), 1);
+	assert_eq!(links.filter("[href ~ /^\\/product\\/\\d+$/i]").length(), 2);
+	assert_eq!(links.filter("[href~/^$/]").length(), 1);
+	assert_eq!(links.filter("[title~/.*/]").length(), 0);
+	// text
+	assert_eq!(links.filter(":matches(/\\d+ items?/)").length(), 3);
+	assert_eq!(links.filter(":matches( /^\\d+ items?$/i )").length(), 4);
+	assert_eq!(links.filter(":matches(/\\(|\\)/)").length(), 0);
+	assert_eq!(root.find("li:has(a:matches(/^1 /))").length(), 2);
+	assert_eq!(root.find("li:not(:matches(/item/i))").length(), 0);
+	Ok(())
+}
+
 #[test]
 fn test_id_selector() -> Result {
 	let html = r##"
@@ -834,6 +906,42 @@ fn test_selector_pseudo_not() -> Result {
 	Ok(())
 }
 
+#[test]
+fn test_selector_pseudo_is_where() -> Result {
+	let html = r##"
+  <!doctype html>
+  <html>
+    <body>
+      <article>
+        <h1><a href="#h1">h1</a></h1>
+        <h2><a href="#h2">h2</a></h2>
+        <h3><span><a href="#h3">h3</a></span></h3>
+        <h4><a href="#h4">h4</a></h4>
+      </article>
+      <aside>
+        <h2><a href="#aside-h2">aside-h2</a></h2>
+      </aside>
+    </body>
+  </html>
+  "##;
+	let root = Vis::load(html)?;
+	let links = root.find("article :is(h1,h2,h3) > a");
+	assert_eq!(links.length(), 2);
+	assert_eq!(links.text(), "h1h2");
+	let links = root.find("article :where(h1, h2, h3) a");
+	assert_eq!(links.length(), 3);
+	assert_eq!(links.text(), "h1h2h3");
+	// nested and complex selectors
+	let links = root.find(":is(article, aside) > :is(h2, :where(h4)) > a");
+	assert_eq!(links.text(), "h2h4aside-h2");
+	let headers = root.find("h2").filter(":is(aside h2)");
+	assert_eq!(headers.text(), "aside-h2");
+	let headers = root.find(":is(h1,h2,h3):not(:is(h1, h2))");
+	assert_eq!(headers.text(), "h3");
+	assert!(root.find("a").is(":where(aside a)"));
+	Ok(())
+}
+
 #[test]
 fn test_selector_pseudo_has() -> Result {
 	let html = r#"
@@ -869,6 +977,115 @@ fn test_selector_pseudo_has() -> Result {
 	Ok(())
 }
 
+#[test]
+fn test_selector_pseudo_has_relative() -> Result {
+	let html = r##"
+  <!doctype html>
+  <html>
+    <body>
+      <ul id="menu">
+        <li><a class="active" href="#1">1</a></li>
+        <li><span><a class="active" href="#2">2</a></span></li>
+        <li><a href="#3">3</a></li>
+      </ul>
+      <div id="content">
+        <h2>title1</h2>
+        <table></table>
+        <h2>title2</h2>
+        <p>text</p>
+        <h2>title3</h2>
+        <p>text</p>
+        <table></table>
+      </div>
+    </body>
+  </html>
+  "##;
+	let root = Vis::load(html)?;
+	// children
+	let items = root.find("li:has(> a.active)");
+	assert_eq!(items.length(), 1);
+	assert_eq!(items.text(), "1");
+	// descendants
+	assert_eq!(root.find("li:has(a.active)").length(), 2);
+	// next sibling
+	let titles = root.find("h2:has(+ table)");
+	assert_eq!(titles.length(), 1);
+	assert_eq!(titles.text(), "title1");
+	// next siblings
+	let titles = root.find("h2:has(~ table)");
+	assert_eq!(titles.text(), "title1title2title3");
+	let titles = root.find("h2:has(~ p + table)");
+	assert_eq!(titles.text(), "title1title2title3");
+	let titles = root.find("h2:has(+ p + table)");
+	assert_eq!(titles.text(), "title3");
+	// selector list
+	let titles = root.find("h2:has(+ table, + p > b, + p ~ table)");
+	assert_eq!(titles.text(), "title1title2title3");
+	// not
+	let titles = root.find("h2:not(:has(+ table))");
+	assert_eq!(titles.text(), "title2title3");
+	// method
+	let lis = root.find("li");
+	assert_eq!(lis.has("> a").length(), 2);
+	assert_eq!(lis.has("+ li > a").length(), 1);
+	assert!(lis.has("> ").is_empty());
+	Ok(())
+}
+
+#[test]
+fn test_selector_custom_pseudo() -> Result {
+	let html = r#"
+  <ul id="list">
+    <li data-price="10">item1</li>
+    <li data-price="25"><b>item2</b></li>
+    <li data-price="40">item3</li>
+    <li>item4</li>
+  </ul>
+  "#;
+	// one by one
+	Vis::register_pseudo(":price-gt({spaces}{nth}{spaces})", |ele, params| {
+		let min = params[0].data["index"].parse::<f64>().unwrap();
+		match ele.get_attribute("data-price") {
+			Some(price) => price.to_string().parse::<f64>().unwrap() > min,
+			None => false,
+		}
+	})?;
+	// nested selector
+	Vis::register_pseudo_all(":has-child({spaces}{selector}{spaces})", |eles, params| {
+		let selector = &params[0].value;
+		eles.filter_by(|_, ele| !Vis::dom(ele).children(selector).is_empty())
+	})?;
+	let root = Vis::load(html)?;
+	let list = root.find("#list");
+	assert_eq!(list.children(":price-gt(20)").length(), 2);
+	assert_eq!(list.children(":price-gt( 30 )").text(), "item3");
+	assert_eq!(list.children(":has-child(b)").text(), "item2");
+	assert_eq!(list.children(":not(:price-gt(20))").length(), 2);
+	assert_eq!(
+		root.find("#list > li:price-gt(20):has-child(b)").length(),
+		1
+	);
+	// wrong names
+	assert!(Vis::register_pseudo("price", |_, _| true).is_err());
+	assert!(Vis::register_pseudo(":(a)", |_, _| true).is_err());
+	// duplicate names
+	assert!(Vis::register_pseudo(":price-gt", |_, _| true).is_err());
+	assert!(Vis::register_pseudo(":not", |_, _| true).is_err());
+	// malformed patterns
+	assert!(Vis::register_pseudo(":price-lt({nth)", |_, _| true).is_err());
+	assert!(Vis::register_pseudo(":price-lt({unknown})", |_, _| true).is_err());
+	// the rules still work after the malformed patterns
+	assert_eq!(list.children(":price-gt(20)").length(), 2);
+	// the custom name doesn't match a part of the other names
+	Vis::register_pseudo(":e", |ele, _| ele.text() == "item4")?;
+	let root = Vis::load("<ul><li>a</li><li>b</li><li></li><li>item4</li></ul>")?;
+	assert_eq!(root.find("li:even").text(), "a");
+	assert_eq!(root.find("li:empty").length(), 1);
+	assert_eq!(root.find("li:e").text(), "item4");
+	assert_eq!(root.find("li:eq(0)").text(), "a");
+	Ok(())
+}
+
 #[test]
 fn test_wrong_selector_splitter() -> Result {
 	let root = Vis::load("<b>anything</b>")?;
@@ -895,3 +1112,806 @@ fn test_wrong_selector_wrong_nested() {
 	let root = Vis::load("<b>anything</b>").unwrap();
 	assert!(root.find(":not(:not(:a)").is_empty());
 }
+
+#[test]
+fn test_compiled_selector() -> Result {
+	let html = r##"
+  <div id="content">
+    <ul class="list">
+      <li class="item"><a href="/1">1</a></li>
+      <li class="item active"><a href="/2">2</a></li>
+      <li class="item"><span>3</span></li>
+    </ul>
+    <ul class="list">
+      <li class="item"><a href="/4">4</a></li>
+    </ul>
+  </div>
+  "##;
+	let selectors = [
+		"ul.list > li a",
+		"#content li:nth-child(2n+1)",
+		".item.active, li:last-child",
+		"li:has(> span)",
+		"ul li:not(.active) a[href]",
+	];
+	let compiled = selectors
+		.iter()
+		.map(|selector| Vis::compile(selector))
+		.collect::<StdResult<Vec<_>, _>>()?;
+	// reuse in multiple documents
+	for _ in 0..2 {
+		let root = Vis::load(html)?;
+		let items = root.find("li");
+		for (selector, compiled) in selectors.iter().zip(compiled.iter()) {
+			assert_eq!(compiled.as_str(), *selector);
+			assert_eq!(
+				root.find_compiled(compiled).outer_html(),
+				root.find(selector).outer_html()
+			);
+			assert_eq!(
+				items.filter_compiled(compiled).outer_html(),
+				items.filter(selector).outer_html()
+			);
+			assert_eq!(items.is_compiled(compiled), items.is(selector));
+			assert_eq!(items.is_all_compiled(compiled), items.is_all(selector));
+			assert_eq!(
+				items.not_compiled(compiled).outer_html(),
+				items.not(selector).outer_html()
+			);
+			assert_eq!(
+				root.find("ul").has_compiled(compiled).length(),
+				root.find("ul").has(selector).length()
+			);
+		}
+	}
+	// empty elements
+	let empty = Vis::load("")?.find("li");
+	assert!(empty.find_compiled(&compiled[0]).is_empty());
+	assert!(!empty.is_compiled(&compiled[0]));
+	// invalid selectors fail up front
+	let err = Vis::compile("li:not-exist").unwrap_err();
+	assert!(err.to_string().contains("li:not-exist"));
+	assert!(Vis::compile("li > ").is_err());
+	assert!(Vis::compile("a[href~/(/]").is_err());
+	// the invalid nested selectors fail up front too
+	assert!(Vis::compile("li:not(a > > b)").is_err());
+	assert!(Vis::compile("li:is(.a, , .b)").is_err());
+	assert!(Vis::compile("ul:has(li:not(a[href~/(/]))").is_err());
+	assert!(Vis::compile("li:nth-child(2n of > )").is_err());
+	Ok(())
+}
+
+#[test]
+fn test_selector_custom_pseudo_after_query() -> Result {
+	let html = r##"
+  <ul>
+    <li>1</li>
+    <li>2</li>
+  </ul>
+  "##;
+	let root = Vis::load(html)?;
+	// the pseudo selector is not registered yet
+	assert!(root.find("li:later-added").is_empty());
+	Vis::register_pseudo(":later-added", |ele, _| ele.text() == "2")?;
+	// the registered pseudo selector can be used in current thread and other threads
+	assert_eq!(root.find("li:later-added").text(), "2");
+	let count = std::thread::spawn(|| {
+		Vis::load("<p>1</p><p>2</p>")
+			.map(|root| root.find("p:later-added").length())
+			.unwrap_or(0)
+	})
+	.join()
+	.unwrap();
+	assert_eq!(count, 1);
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_positional() -> Result {
+	let html = r##"
+  <table>
+    <tr class="head"><th>name</th></tr>
+    <tr><td>a</td></tr>
+    <tr><td>b</td></tr>
+  </table>
+  <ul id="first">
+    <li>1</li>
+    <li class="active">2</li>
+    <li>3</li>
+  </ul>
+  <ul id="second">
+    <li class="active">4</li>
+    <li>5</li>
+  </ul>
+  "##;
+	let root = Vis::load(html)?;
+	// :gt
+	let rows = root.find("tr:gt(0)");
+	assert_eq!(rows.length(), 2);
+	assert_eq!(rows.text(), "ab");
+	// :even, :odd work on the matched set, across the parents
+	assert_eq!(root.find("li:even").text(), "135");
+	assert_eq!(root.find("li:odd").text(), "24");
+	// :first, :last
+	assert_eq!(root.find("li:first").text(), "1");
+	assert_eq!(root.find("ul li:last").text(), "5");
+	assert_eq!(root.find(":first").length(), 1);
+	assert_eq!(root.find("ul:last > li:first").text(), "4");
+	// :eq, :lt
+	assert_eq!(root.find("li:eq(3)").text(), "4");
+	assert_eq!(root.find("li:eq(-1)").text(), "5");
+	assert_eq!(root.find("li:eq(10)").length(), 0);
+	assert_eq!(root.find("li:lt(2)").text(), "12");
+	assert_eq!(root.find("li:lt(-3)").text(), "12");
+	// the rules after a positional rule apply to its result
+	assert_eq!(root.find("li.active:first").text(), "2");
+	assert_eq!(root.find("li:first.active").length(), 0);
+	assert_eq!(root.find("li:gt(1).active").text(), "4");
+	// sibling position selectors are not affected
+	assert_eq!(root.find("li:first-child").text(), "14");
+	assert_eq!(root.find("li:last-child").text(), "35");
+	// filter
+	let items = root.find("li");
+	assert_eq!(items.filter(":even").text(), "135");
+	assert_eq!(items.filter(":odd:first").text(), "2");
+	assert!(items.eq(4).is(":last"));
+	// wrong index
+	assert!(Vis::compile("li:eq(a)").is_err());
+	assert!(Vis::compile("li:eq(99999999999999999999999)").is_err());
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_form_state() -> Result {
+	let html = r##"
+  <form id="login">
+    <fieldset disabled>
+      <legend><input id="in-legend" /></legend>
+      <input id="in-fieldset" required />
+      <fieldset><legend><button type="reset" id="nested-legend">nested</button></legend></fieldset>
+    </fieldset>
+    <input id="name" type="text" required />
+    <input id="readonly" type="text" readonly />
+    <input id="hidden" type="hidden" required />
+    <input id="check" type="checkbox" checked />
+    <input type="radio" name="sex" value="male" />
+    <input type="radio" name="sex" value="female" />
+    <input type="radio" name="agree" value="yes" checked />
+    <textarea id="content" disabled></textarea>
+    <select id="single">
+      <option value="1">1</option>
+      <option value="2" disabled>2</option>
+    </select>
+    <select id="multiple" multiple>
+      <optgroup label="group" disabled><option value="a">a</option></optgroup>
+      <option value="b" selected>b</option>
+    </select>
+    <button type="button" id="reset">reset</button>
+    <button id="submit">submit</button>
+    <input type="submit" id="submit2" />
+  </form>
+  <div contenteditable><p id="editable">editable</p><p contenteditable="false" id="uneditable"></p></div>
+  <progress id="loading"></progress>
+  <progress id="loaded" value="100" max="100"></progress>
+  "##;
+	let root = Vis::load(html)?;
+	let ids = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| {
+				ele
+					.get_attribute("id")
+					.map(|id| id.to_string())
+					.unwrap_or_default()
+			})
+			.join(",")
+	};
+	// :disabled, :enabled
+	assert_eq!(
+		root.find(":disabled").length(),
+		// fieldset, input#in-fieldset, fieldset, button#nested-legend, textarea, option, optgroup, option
+		8
+	);
+	assert_eq!(
+		ids("input:disabled, button:disabled"),
+		"in-fieldset,nested-legend"
+	);
+	assert!(root.find("#in-legend").is(":enabled"));
+	assert!(root.find("#name").is(":enabled"));
+	assert_eq!(root.find("select:enabled").length(), 2);
+	assert_eq!(root.find("div:enabled").length(), 0);
+	// :required, :optional
+	assert_eq!(ids(":required"), "in-fieldset,name");
+	assert!(root.find("#hidden").is(":optional"));
+	assert!(root.find("select").is_all(":optional"));
+	assert_eq!(root.find("div:optional").length(), 0);
+	// :read-write, :read-only
+	assert_eq!(ids("input:read-write"), "in-legend,name");
+	assert_eq!(ids("p:read-write"), "editable");
+	assert!(root
+		.find("#readonly, #content, #in-fieldset, #uneditable")
+		.is_all(":read-only"));
+	assert!(root.find("div[contenteditable]").is(":read-write"));
+	// :selected, include the default option of the single select
+	assert_eq!(root.find(":selected").text(), "1b");
+	assert_eq!(root.find("#multiple :selected").text(), "b");
+	// :indeterminate
+	assert_eq!(root.find("input:indeterminate").length(), 2);
+	assert_eq!(root.find("[name='sex']:indeterminate").length(), 2);
+	assert_eq!(ids("progress:indeterminate"), "loading");
+	// :default
+	// input#check, input[name='agree'], option, button#submit
+	assert_eq!(root.find(":default").length(), 4);
+	assert_eq!(ids("button:default, [type='submit']:default"), "submit");
+	assert_eq!(root.find("option:default").text(), "b");
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_form_owner() -> Result {
+	let html = r##"
+  <input type="submit" id="outer-submit" form="login" />
+  <input type="radio" name="remember" id="outer-radio" form="login" checked />
+  <form id="login">
+    <input type="radio" name="remember" id="inner-radio" />
+    <input type="radio" name="lang" id="lang" />
+    <button id="inner-submit">login</button>
+  </form>
+  <form id="search">
+    <input type="radio" name="lang" id="search-lang" form="other" />
+    <input type="radio" name="lang" id="other-lang" form="none" checked />
+    <button id="search-submit">search</button>
+  </form>
+  <div id="other"></div>
+  "##;
+	let root = Vis::load(html)?;
+	let ids = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| {
+				ele
+					.get_attribute("id")
+					.map(|id| id.to_string())
+					.unwrap_or_default()
+			})
+			.join(",")
+	};
+	// the radio associated by the 'form' attribute is in the same group
+	assert!(root.find("#inner-radio").is_all(":not(:indeterminate)"));
+	assert!(root.find("#lang").is(":indeterminate"));
+	// the radios with a wrong 'form' attribute have no form owner, so they are in the same group
+	assert!(root.find("#search-lang").is_all(":not(:indeterminate)"));
+	// the default button is the first submit button of the form owner in tree order
+	assert_eq!(
+		ids("button:default, input:default"),
+		"outer-submit,outer-radio,other-lang,search-submit"
+	);
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_input_types() -> Result {
+	let html = r##"
+  <form>
+    <input name="no-type" />
+    <input name="text" type="TEXT" />
+    <input name="unknown" type="unknown" />
+    <input name="password" type="password" />
+    <input name="radio" type="radio" />
+    <input name="checkbox" type="checkbox" />
+    <input name="file" type="file" />
+    <input name="image" type="image" />
+    <input name="input-button" type="button" />
+    <input name="input-reset" type="reset" />
+    <input name="hidden" type="hidden" />
+    <button name="button">button</button>
+    <button name="button-reset" type="reset">reset</button>
+  </form>
+  "##;
+	let root = Vis::load(html)?;
+	let names = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| {
+				ele
+					.get_attribute("name")
+					.map(|name| name.to_string())
+					.unwrap_or_default()
+			})
+			.join(",")
+	};
+	assert_eq!(names(":text"), "no-type,text");
+	assert_eq!(names(":password"), "password");
+	assert_eq!(names(":radio"), "radio");
+	assert_eq!(names(":checkbox"), "checkbox");
+	assert_eq!(names(":file"), "file");
+	assert_eq!(names(":image"), "image");
+	assert_eq!(names(":button"), "input-button,button,button-reset");
+	assert_eq!(names(":reset"), "input-reset,button-reset");
+	assert_eq!(names(":hidden"), "hidden");
+	assert_eq!(names("form > :not(:text):first"), "unknown");
+	assert!(root.find("input").eq(1).is(":text"));
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_lang_dir() -> Result {
+	let html = r##"
+  <html lang="en">
+    <body>
+      <p id="en">english</p>
+      <div lang="de-DE">
+        <p id="de">deutsch</p>
+        <section lang="DE-at"><p id="de-at">österreich</p></section>
+        <p lang="" id="unknown">unknown</p>
+      </div>
+      <p lang="den" id="den">not german</p>
+      <div dir="rtl">
+        <p id="rtl">مرحبا</p>
+        <p dir="ltr" id="ltr">hello</p>
+        <p dir="auto" id="auto-ltr">hello</p>
+        <p dir="invalid" id="inherit-rtl">inherit</p>
+      </div>
+      <p dir="auto" id="auto-rtl">שלום</p>
+      <bdi id="bdi">مرحبا</bdi>
+    </body>
+  </html>
+  "##;
+	let root = Vis::load(html)?;
+	let ids = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| {
+				ele
+					.get_attribute("id")
+					.map(|id| id.to_string())
+					.unwrap_or_default()
+			})
+			.join(",")
+	};
+	// :lang
+	assert_eq!(ids("p:lang(de)"), "de,de-at");
+	assert_eq!(ids("p:lang(de-at)"), "de-at");
+	assert_eq!(ids("p:lang(\"DE-DE\")"), "de");
+	assert_eq!(
+		ids("p:lang(en)"),
+		"en,rtl,ltr,auto-ltr,inherit-rtl,auto-rtl"
+	);
+	assert_eq!(ids("p:lang(fr, de-at)"), "de-at");
+	assert_eq!(root.find("p:lang(*)").length(), 9);
+	assert!(root.find("#unknown, #den").is_all(":not(:lang(de))"));
+	// :dir
+	assert_eq!(ids("p:dir(rtl)"), "rtl,inherit-rtl,auto-rtl");
+	assert_eq!(ids("p:dir(ltr)"), "en,de,de-at,unknown,den,ltr,auto-ltr");
+	assert!(root.find("#bdi").is(":dir(rtl)"));
+	assert!(root.find("div").eq(0).is(":dir(ltr)"));
+	assert_eq!(root.find(":dir(other)").length(), 0);
+	// the content language meta
+	let html = r##"
+  <html>
+    <head><meta http-equiv="Content-Language" content="fr-CA" /></head>
+    <body><p>bonjour</p><p lang="en">hello</p></body>
+  </html>
+  "##;
+	let root = Vis::load(html)?;
+	assert_eq!(root.find("p:lang(fr)").text(), "bonjour");
+	assert_eq!(root.find("p:lang(en)").text(), "hello");
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_nth_child_of() -> Result {
+	let html = r##"
+  <ul id="first">
+    <li class="item">1</li>
+    <li class="ad">ad</li>
+    <li class="item">2</li>
+    <li class="item">3</li>
+    <li class="ad">ad</li>
+    <li class="item">4</li>
+  </ul>
+  <ul id="second">
+    <li class="ad">ad</li>
+    <li class="item">5</li>
+    <li class="item">6</li>
+  </ul>
+  "##;
+	let root = Vis::load(html)?;
+	assert_eq!(root.find("li:nth-child(2n of .item)").text(), "246");
+	assert_eq!(root.find("li:nth-child(odd of .item)").text(), "135");
+	assert_eq!(root.find(":nth-child(1 of .item, .other)").text(), "15");
+	assert_eq!(root.find("li:nth-last-child(1 of :not(.ad))").text(), "46");
+	assert_eq!(
+		root.find("#first li:nth-last-child(-n+2 of .item)").text(),
+		"34"
+	);
+	// the element must match the selector list too
+	assert_eq!(root.find("li.ad:nth-child(1 of .item)").length(), 0);
+	// without `of`, count all the siblings
+	assert_eq!(root.find("li:nth-child(2n)").length(), 4);
+	assert_eq!(root.find("#second li:nth-child(1)").text(), "ad");
+	// filter
+	let items = root.find("li");
+	assert_eq!(items.filter(":nth-child(3n of .item)").text(), "3");
+	assert!(items.eq(0).is(":nth-child(1 of li.item)"));
+	// nested
+	assert_eq!(
+		root
+			.find("ul:has(li:nth-child(3 of .item):not(.ad) ~ .ad)")
+			.attr("id")
+			.map(|id| id.to_string()),
+		Some(String::from("first"))
+	);
+	// wrong selector list
+	assert!(Vis::compile("li:nth-child(2nof .item)").is_err());
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_link_and_structure() -> Result {
+	let html = r##"
+  <div id="content">
+    <a href="/home" id="home">home</a>
+    <a name="intro" id="anchor">no href</a>
+    <map><area href="/area" id="area" /></map>
+    <p id="blank">  </p>
+    <p id="comment"><!--comment--></p>
+    <p id="text">text</p>
+    <h2 id="intro">intro</h2>
+    <a name="section" id="section1"></a>
+    <a name="section" id="section2"></a>
+  </div>
+  <div id="visible">
+    <span hidden id="hidden-attr">hidden</span>
+    <input type="HIDDEN" id="hidden-input" />
+    <div style="color: red; display : none" id="display-none"><b id="in-display-none">b</b></div>
+    <div style="visibility:hidden" id="visibility-hidden">
+      <i id="in-visibility-hidden">i</i>
+      <i style="visibility: visible !important" id="visibility-visible">i</i>
+    </div>
+    <p style="display:none; display:block" id="display-block">p</p>
+  </div>
+  "##;
+	let root = Vis::load(html)?;
+	let ids = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| {
+				ele
+					.get_attribute("id")
+					.map(|id| id.to_string())
+					.unwrap_or_default()
+			})
+			.join(",")
+	};
+	// :any-link, :link
+	assert_eq!(ids(":any-link"), "home,area");
+	assert_eq!(ids(":link"), "home,area");
+	// :target
+	assert_eq!(ids(":target(intro)"), "intro");
+	assert_eq!(ids(":target(#intro)"), "intro");
+	assert_eq!(ids(":target('section')"), "section1");
+	assert_eq!(ids(":target(none)"), "");
+	// :blank, :empty, :parent
+	assert_eq!(ids("p:blank"), "blank,comment");
+	assert_eq!(ids("p:empty"), "comment");
+	assert_eq!(ids("p:parent"), "blank,text,display-block");
+	assert_eq!(ids("#content a:parent"), "home,anchor");
+	// :hidden, :visible
+	assert_eq!(
+		ids("#visible :hidden"),
+		"hidden-attr,hidden-input,display-none,in-display-none,visibility-hidden,in-visibility-hidden"
+	);
+	assert_eq!(ids("#visible :visible"), "visibility-visible,display-block");
+	let doc = Vis::load("<html><head><title>title</title></head><body>body</body></html>")?;
+	assert!(doc.find("title").is(":hidden"));
+	assert!(doc.find("body").is(":visible"));
+	assert!(root.find("#content").is(":visible"));
+	Ok(())
+}
+
+#[test]
+fn test_selector_pseudo_icontains_and_contains_own() -> Result {
+	let html = r##"
+  <div class="list">
+    <div class="item">Big <b>SALE</b> today</div>
+    <div class="item">Sale ends <i>soon</i></div>
+    <div class="item">Nothing</div>
+  </div>
+  "##;
+	let root = Vis::load(html)?;
+	// case-sensitive, match the descendant texts
+	assert_eq!(root.find(".item:contains('SALE')").length(), 1);
+	assert_eq!(root.find("div:contains('SALE')").length(), 2);
+	// case-insensitive
+	assert_eq!(root.find(".item:icontains(\"sale\")").length(), 2);
+	assert_eq!(root.find("b:icontains(sale)").text(), "SALE");
+	assert_eq!(root.find(":icontains('ENDS soon')").length(), 2);
+	// only the direct text children
+	assert_eq!(root.find("div:contains-own('SALE')").length(), 0);
+	assert_eq!(root.find(":contains-own('SALE')").text(), "SALE");
+	assert_eq!(root.find("div:contains-own('today')").length(), 1);
+	assert_eq!(root.find("div:contains-own('ends soon')").length(), 0);
+	assert_eq!(root.find("div:contains-own(Sale)").text(), "Sale ends soon");
+	// empty search matches all
+	assert_eq!(root.find(".item:contains-own('')").length(), 3);
+	Ok(())
+}
+
+#[test]
+fn test_selector_namespace() -> Result {
+	let html = r##"
+  <div id="ns">
+    <svg id="svg" viewBox="0 0 10 10">
+      <a id="svg-link" xlink:href="#rect" href="#plain"><rect id="rect" /></a>
+      <foreignObject id="foreign"><div id="in-foreign"></div></foreignObject>
+    </svg>
+    <math id="math"><mi id="mi">x</mi></math>
+    <a id="html-link" href="/home">home</a>
+    <feed xmlns="urn:atom" id="feed"><entry id="entry"></entry><a id="atom-link" xmlns="" /></feed>
+    <r:item id="rss-item" xmlns:r="urn:rss" r:lang="en"><r:title id="rss-title"></r:title></r:item>
+  </div>
+  "##;
+	let root = Vis::load(html)?;
+	let ids = |selector: &str| -> String {
+		root
+			.find(selector)
+			.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+			.join(",")
+	};
+	// element namespaces
+	assert_eq!(ids("#ns svg|*"), "svg,svg-link,rect,foreign");
+	assert_eq!(ids("#ns svg|a"), "svg-link");
+	assert_eq!(ids("#ns xhtml|div"), "in-foreign");
+	assert_eq!(ids("#ns math|*"), "math,mi");
+	assert_eq!(ids("#ns *|a"), "svg-link,html-link,atom-link");
+	assert_eq!(ids("#ns xhtml|a"), "html-link");
+	assert_eq!(ids("#ns |a"), "atom-link");
+	assert_eq!(ids("#ns svg|a > svg|rect"), "rect");
+	// attribute namespaces
+	assert_eq!(ids("#ns [xlink|href]"), "svg-link");
+	assert_eq!(ids("#ns [xlink|href='#rect']"), "svg-link");
+	assert_eq!(ids("#ns [|href]"), "svg-link,html-link");
+	assert_eq!(ids("#ns [*|href^='#']"), "svg-link");
+	assert_eq!(ids("#ns [xlink:href]"), "svg-link");
+	// the `|=` is still the attribute value operator
+	assert_eq!(ids("#ns [id|=rss]"), "rss-item,rss-title");
+	// the prefixes must be declared
+	assert!(Vis::compile("atom|entry").is_err());
+	assert!(Vis::compile("[atom|href]").is_err());
+	let atom = Vis::with_namespaces(&[("atom", "urn:atom"), ("r", "urn:rss")], || {
+		Vis::compile("atom|*, r|title, [r|lang=en]")
+	})?;
+	assert_eq!(
+		root
+			.find_compiled(&atom)
+			.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+			.join(","),
+		"feed,entry,rss-item,rss-title"
+	);
+	assert!(Vis::compile("atom|entry").is_err());
+	// the default namespace applies to the type selectors
+	let svg_links = Vis::with_namespaces(&[("", "http://www.w3.org/2000/svg")], || {
+		// the outer declarations are inherited by the nested calls
+		Vis::with_namespaces(&[("atom", "urn:atom")], || root.find("#ns a, atom|entry"))
+	});
+	assert_eq!(
+		svg_links
+			.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+			.join(","),
+		"svg-link,entry"
+	);
+	assert_eq!(ids("#ns a"), "svg-link,html-link,atom-link");
+	// the default namespace also applies to the `*`
+	let svg_all = Vis::with_namespaces(&[("", "http://www.w3.org/2000/svg")], || root.find("#ns *"));
+	assert_eq!(
+		svg_all
+			.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+			.join(","),
+		"svg,svg-link,rect,foreign"
+	);
+	// the compiled selectors keep the namespaces outside the handle, the nested selectors too
+	let (entry, not_atom, atom_or_rss, svg_any) = Vis::with_namespaces(
+		&[
+			("", "http://www.w3.org/2000/svg"),
+			("atom", "urn:atom"),
+			("r", "urn:rss"),
+		],
+		|| {
+			Ok::<_, BoxDynError>((
+				Vis::compile("*|*:has(> atom|entry)")?,
+				Vis::compile("#feed > *|*:not(atom|*)")?,
+				Vis::compile("*|*:is(atom|entry, r|title)")?,
+				Vis::compile("#ns > *")?,
+			))
+		},
+	)?;
+	let ids_compiled = |selector: &CompiledSelector| -> String {
+		root
+			.find_compiled(selector)
+			.map(|_, ele| ele.get_attribute("id").unwrap().to_string())
+			.join(",")
+	};
+	assert_eq!(ids_compiled(&entry), "feed");
+	assert_eq!(ids_compiled(&not_atom), "atom-link");
+	assert_eq!(ids_compiled(&atom_or_rss), "entry,rss-title");
+	assert_eq!(ids_compiled(&svg_any), "svg");
+	assert!(root.find("#feed").is_compiled(&entry));
+	assert_eq!(
+		root.find("#feed > *").filter_compiled(&not_atom).length(),
+		1
+	);
+	Ok(())
+}
+
+#[test]
+fn test_selector_specificity() -> Result {
+	let specificity =
+		|selector: &str| -> Vec<Specificity> { Vis::compile(selector).unwrap().specificity() };
+	assert_eq!(specificity("*"), vec![Specificity(0, 0, 0)]);
+	assert_eq!(specificity("li"), vec![Specificity(0, 0, 1)]);
+	assert_eq!(specificity("ul > li + li ~ li"), vec![Specificity(0, 0, 4)]);
+	assert_eq!(
+		specificity("#id.a.b[title]:first-child"),
+		vec![Specificity(1, 4, 0)]
+	);
+	assert_eq!(
+		specificity("a, .b, #c"),
+		vec![
+			Specificity(0, 0, 1),
+			Specificity(0, 1, 0),
+			Specificity(1, 0, 0)
+		]
+	);
+	// the most specific selector in the arguments
+	assert_eq!(specificity(":is(.a, #b, c)"), vec![Specificity(1, 0, 0)]);
+	assert_eq!(
+		specificity("li:not(.a.b, span)"),
+		vec![Specificity(0, 2, 1)]
+	);
+	assert_eq!(specificity("div:has(> p.a)"), vec![Specificity(0, 1, 2)]);
+	assert_eq!(specificity(":where(#a .b) p"), vec![Specificity(0, 0, 1)]);
+	assert_eq!(specificity(":is(:not(#a), .b)"), vec![Specificity(1, 0, 0)]);
+	assert_eq!(
+		specificity("li:nth-child(2n of .a, #b)"),
+		vec![Specificity(1, 1, 1)]
+	);
+	assert_eq!(specificity("li:nth-child(2n)"), vec![Specificity(0, 1, 1)]);
+	// namespaces
+	assert_eq!(specificity("svg|*"), vec![Specificity(0, 0, 0)]);
+	assert_eq!(
+		specificity("svg|rect[xlink|href]"),
+		vec![Specificity(0, 1, 1)]
+	);
+	let compiled = Vis::with_namespaces(&[("atom", "urn:atom")], || {
+		Vis::compile(":is(atom|entry, atom|*)")
+	})?;
+	assert_eq!(compiled.specificity(), vec![Specificity(0, 0, 1)]);
+	Ok(())
+}
+
+#[test]
+fn test_selector_explain() -> Result {
+	let html = r##"
+  <div id="content" class="article">
+    <ul class="list">
+      <li class="item"><a class="title" href="/a">a</a></li>
+      <li class="item ad"><a class="title" href="/b">b</a></li>
+    </ul>
+  </div>
+  "##;
+	let root = Vis::load(html)?;
+	let titles = root.find("a");
+	let first = titles.get(0).unwrap();
+	let second = titles.get(1).unwrap();
+	let selector = Vis::compile("#content   .list>li:not(.ad) a.title, div.news a, span")?;
+	let explanations = selector.explain(first);
+	assert_eq!(explanations.len(), 3);
+	assert!(explanations[0].is_matched());
+	assert_eq!(
+		explanations[0].selector,
+		"#content .list > li:not(.ad) a.title"
+	);
+	assert_eq!(explanations[0].specificity, Specificity(1, 3, 2));
+	assert_eq!(
+		explanations[0].segments,
+		vec![
+			(Combinator::ChildrenAll, String::from("#content")),
+			(Combinator::ChildrenAll, String::from(".list")),
+			(Combinator::Children, String::from("li:not(.ad)")),
+			(Combinator::ChildrenAll, String::from("a.title")),
+		]
+	);
+	assert_eq!(explanations[1].failed, Some(0));
+	assert_eq!(explanations[1].failed_segment(), Some("div.news"));
+	assert_eq!(explanations[2].failed_segment(), Some("span"));
+	assert_eq!(
+		explanations[2].to_string(),
+		"`span` (0, 0, 1) failed at `span`"
+	);
+	// the ad item fails at the `li`
+	let explanations = selector.explain(second);
+	assert_eq!(explanations[0].failed, Some(2));
+	assert_eq!(
+		explanations[0].to_string(),
+		"`#content .list > li:not(.ad) a.title` (1, 3, 2) failed at `li:not(.ad)`"
+	);
+	// the namespaces are kept
+	let doc = Vis::load(r#"<feed xmlns="urn:atom"><entry><title>t</title></entry></feed>"#)?;
+	let selector = Vis::with_namespaces(&[("atom", "urn:atom")], || {
+		Vis::compile("atom|feed > atom|entry > atom|title, atom|entry > atom|link")
+	})?;
+	let title = doc.find("title");
+	let explanations = selector.explain(title.get(0).unwrap());
+	assert!(explanations[0].is_matched());
+	assert_eq!(explanations[1].failed_segment(), Some("atom|link"));
+	Ok(())
+}
+
+#[test]
+fn test_selector_normalize() -> Result {
+	let normalize = |selector: &str| -> String { Vis::compile(selector).unwrap().to_string() };
+	let cases = [
+		("  div   p  ", "div p"),
+		("ul>li+li~li ,a", "ul > li + li ~ li, a"),
+		("#a\\.b.c\\:d", "#a\\.b.c\\:d"),
+		("[ title ]", "[title]"),
+		("[title = 'a b' I]", "[title=\"a b\" i]"),
+		(
+			"[lang|=en][data-id=\"1\"][type=text s]",
+			"[lang|=\"en\"][data-id=\"1\"][type=\"text\" s]",
+		),
+		("[title='a\"b'][alt=\"a'b\"]", "[title='a\"b'][alt=\"a'b\"]"),
+		("[title='a\\'b']", "[title='a\\'b']"),
+		("a[ href ~ /\\d+  x/i ]", "a[href~/\\d+  x/i]"),
+		("svg|rect[ xlink|href ], *|*", "svg|rect[xlink|href], *|*"),
+		(
+			"li:nth-child( 2n + 1 ):nth-last-of-type( odd )",
+			"li:nth-child(2n+1):nth-last-of-type(odd)",
+		),
+		(
+			"li:nth-child(2n   of  .a ,.b )",
+			"li:nth-child(2n of .a, .b)",
+		),
+		("div:not( .a ,  p>span )", "div:not(.a, p > span)"),
+		("div:has(>p)  :is( a,b )", "div:has(> p) :is(a, b)"),
+		(":not(:not( .a ))", ":not(:not(.a))"),
+		(
+			":contains( 'a  b' ):contains(\"c\")",
+			":contains(\"a  b\"):contains(\"c\")",
+		),
+		(":contains('a\"b')", ":contains('a\"b')"),
+		(":lang( en,   fr ):dir( rtl )", ":lang(en, fr):dir(rtl)"),
+		("li:eq( -1 ), li:gt(0)", "li:eq(-1), li:gt(0)"),
+		(":matches( /a  b/i )", ":matches(/a  b/i)"),
+		("DIV.Item", "DIV.Item"),
+	];
+	let html = r##"
+  <div class="list">
+    <ul>
+      <li class="a" lang="en" title="a b"><a href="/1">a  b</a></li>
+      <li class="b"><p><span>c</span></p></li>
+      <li class="a"><a href="/2" title='a"b'>c</a></li>
+    </ul>
+  </div>
+  "##;
+	let root = Vis::load(html)?;
+	for (selector, expected) in cases {
+		let normalized = normalize(selector);
+		assert_eq!(normalized, expected, "normalize '{}'", selector);
+		// parse -> print -> parse
+		assert_eq!(normalize(&normalized), normalized);
+		assert_eq!(
+			root.find(selector).outer_html(),
+			root.find(&normalized).outer_html(),
+			"find '{}'",
+			selector
+		);
+	}
+	// the namespaces are kept
+	let selector = Vis::with_namespaces(&[("atom", "urn:atom")], || {
+		Vis::compile("atom|entry>atom|title")
+	})?;
+	assert_eq!(selector.to_string(), "atom|entry > atom|title");
+	Ok(())
+}
@@ -1,5 +1,5 @@
 use std::result::Result as StdResult;
-use visdom::types::BoxDynError;
+use visdom::types::{BoxDynError, INodeType};
 use visdom::Vis;
 type Result = StdResult<(), BoxDynError>;
 
@@ -154,6 +154,92 @@ fn test_node_trait() -> Result {
 	Ok(())
 }
 
+#[test]
+#[cfg(any(feature = "destroy", feature = "insertion"))]
+fn test_uncare_node_trait() -> Result {
+	let html = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">
+    <html>
+      <head><!--[if IE]><link href="ie.css"><![endif]--><!-- keep --></head>
+      <body><svg><text><![CDATA[a < b]]></text></svg></body>
+    </html>
+  "#;
+	let root = Vis::load(html)?;
+	// doctype
+	let doctype = root
+		.contents()
+		.filter_type(INodeType::HTMLDOCTYPE)
+		.into_iter()
+		.next()
+		.and_then(|node| node.typed().into_uncare_node())
+		.unwrap();
+	assert_eq!(doctype.name().as_deref(), Some("html"));
+	assert_eq!(
+		doctype.public_id().as_deref(),
+		Some("-//W3C//DTD HTML 4.01//EN")
+	);
+	assert_eq!(
+		doctype.system_id().as_deref(),
+		Some("http://www.w3.org/TR/html4/strict.dtd")
+	);
+	type DoctypeParts = (Option<String>, Option<String>, Option<String>);
+	let get_doctype = |html: &str| -> StdResult<DoctypeParts, BoxDynError> {
+		let node = Vis::load(html)?
+			.contents()
+			.into_iter()
+			.next()
+			.and_then(|node| node.typed().into_uncare_node())
+			.unwrap();
+		Ok((node.name(), node.public_id(), node.system_id()))
+	};
+	assert_eq!(
+		get_doctype("<!doctype html>")?,
+		(Some(String::from("html")), None, None)
+	);
+	assert_eq!(
+		get_doctype(r#"<!DOCTYPE svg SYSTEM "svg.dtd">"#)?,
+		(
+			Some(String::from("svg")),
+			None,
+			Some(String::from("svg.dtd"))
+		)
+	);
+	// comments, remove the conditional comments
+	let head = root.find("head");
+	for node in head.contents().filter_type(INodeType::Comment) {
+		let comment = node.typed().into_uncare_node().unwrap();
+		assert!(comment.name().is_none());
+		if comment.data().unwrap().starts_with("[if ") {
+			comment.remove();
+		}
+	}
+	assert_eq!(head.html(), "<!-- keep -->");
+	let mut comment = head
+		.contents()
+		.into_iter()
+		.next()
+		.and_then(|node| node.typed().into_uncare_node())
+		.unwrap();
+	comment.set_data("changed");
+	assert_eq!(head.html(), "<!--changed-->");
+	// the invalid data is not allowed
+	comment.set_data("a-->b");
+	assert_eq!(comment.data().as_deref(), Some("changed"));
+	// cdata
+	let text = root.find("svg text");
+	let mut cdata = text
+		.contents()
+		.into_iter()
+		.next()
+		.and_then(|node| node.typed().into_uncare_node())
+		.unwrap();
+	assert_eq!(cdata.data().as_deref(), Some("a < b"));
+	cdata.set_data("a > b");
+	assert_eq!(text.html(), "<![CDATA[a > b]]>");
+	assert!(Vis::cdata("]]>").is_err());
+	assert!(Vis::comment("->").is_err());
+	Ok(())
+}
+
 #[test]
 #[cfg(feature = "text")]
 fn test_node_text_trait() -> Result {